@@ -0,0 +1,18 @@
+// FNV-1a, stable across builds and platforms unlike `DefaultHasher`,
+// for values derived from names, e.g. chatter colors and window labels
+pub fn fnv1a_hash(text: &str) -> u32 {
+    text.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_hash() {
+        assert_eq!(fnv1a_hash(""), 0x811c9dc5);
+        assert_eq!(fnv1a_hash("a"), 0xe40c292c);
+    }
+}
@@ -1,19 +1,24 @@
+mod hash;
 mod settings;
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
-pub use settings::AppSettings;
+pub use hash::fnv1a_hash;
+pub use settings::{
+    AppSettings, EmoteAnimation, EmoteScale, HotkeySettings, InteractModifier, RateLimitStrategy,
+    SoundRule, SoundSettings, TextShadowStyle, TtsPolicy,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GigaChatSender {
     pub login: String,
     pub display_name: String,
-    pub color: String,
+    pub color: Option<String>,
 }
 
 impl GigaChatSender {
-    pub fn new(login: String, display_name: String, color: String) -> Self {
+    pub fn new(login: String, display_name: String, color: Option<String>) -> Self {
         Self {
             login,
             display_name,
@@ -24,10 +29,14 @@ impl GigaChatSender {
 
 impl Default for GigaChatSender {
     fn default() -> Self {
-        Self::new("".to_string(), "".to_string(), "#000000".to_string())
+        Self::new("".to_string(), "".to_string(), None)
     }
 }
 
+fn default_repeat_count() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GigaChatMessage {
     pub sender: GigaChatSender,
@@ -35,6 +44,12 @@ pub struct GigaChatMessage {
     pub message_html: String,
     pub message_id: String,
     pub timestamp: chrono::DateTime<Utc>,
+    // Number of duplicate messages collapsed into this one
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+    // Message from the app itself, e.g. about emote changes, shown without a sender
+    #[serde(default)]
+    pub system: bool,
 }
 
 impl GigaChatMessage {
@@ -51,6 +66,21 @@ impl GigaChatMessage {
             message_html: message_with_emotes,
             message_id,
             timestamp,
+            repeat_count: default_repeat_count(),
+            system: false,
+        }
+    }
+
+    pub fn new_system(message: String, message_html: String, message_id: String) -> Self {
+        Self {
+            system: true,
+            ..Self::new(
+                GigaChatSender::default(),
+                message,
+                message_html,
+                message_id,
+                Utc::now(),
+            )
         }
     }
 }
@@ -78,7 +108,150 @@ pub struct UpdateIgnoreEventsArgs {
     pub ignore_events: bool,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateInteractionEventArgs {
+    pub interactive: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UpdateMessageEventArgs {
+    pub message_id: String,
+    pub repeat_count: u32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UpdateAlwaysOnTopEventArgs {
     pub always_on_top: bool,
 }
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ThemesInfo {
+    pub themes: Vec<String>,
+    pub user_theme_path: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SoundsInfo {
+    pub sounds: Vec<String>,
+    pub user_sounds_path: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HotkeyConflict {
+    pub action: String,
+    pub accelerator: String,
+    pub reason: String,
+}
+
+// What emote provider can fetch
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EmoteProviderCapabilities {
+    pub global_emotes: bool,
+    pub channel_emotes: bool,
+    pub badges: bool,
+    pub live_updates: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EmoteProviderStatus {
+    // Nothing was fetched yet
+    Unknown,
+    Ok,
+    Failed,
+}
+
+// Result of the last global or channel emotes fetch of a provider
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmoteFetchHealth {
+    pub status: EmoteProviderStatus,
+    pub last_error: Option<String>,
+    pub last_fetch: Option<chrono::DateTime<Utc>>,
+    // Emotes returned by the last successful fetch
+    pub emote_count: Option<u32>,
+    pub latency_ms: Option<u64>,
+}
+
+impl Default for EmoteFetchHealth {
+    fn default() -> Self {
+        Self {
+            status: EmoteProviderStatus::Unknown,
+            last_error: None,
+            last_fetch: None,
+            emote_count: None,
+            latency_ms: None,
+        }
+    }
+}
+
+// Global and channel fetches are tracked apart, so that one doesn't hide failures of the other
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct EmoteProviderHealth {
+    pub global: EmoteFetchHealth,
+    pub channel: EmoteFetchHealth,
+}
+
+impl EmoteProviderHealth {
+    // Failed when any fetch failed, OK when any fetch succeeded
+    pub fn get_status(&self) -> EmoteProviderStatus {
+        let statuses = [self.global.status, self.channel.status];
+
+        if statuses.contains(&EmoteProviderStatus::Failed) {
+            EmoteProviderStatus::Failed
+        } else if statuses.contains(&EmoteProviderStatus::Ok) {
+            EmoteProviderStatus::Ok
+        } else {
+            EmoteProviderStatus::Unknown
+        }
+    }
+}
+
+// Emote listed in the emote browser
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmoteInfo {
+    pub code: String,
+    pub url: String,
+    // Channel emote, otherwise global
+    pub channel: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmotesGroup {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub emotes: Vec<EmoteInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmoteStat {
+    pub code: String,
+    pub url: String,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChatterStat {
+    pub login: String,
+    pub display_name: String,
+    pub message_count: u32,
+}
+
+// Chat activity over the recent sliding window
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChatStats {
+    pub window_secs: u64,
+    pub messages_per_minute: u32,
+    pub unique_chatters: u32,
+    pub top_emotes: Vec<EmoteStat>,
+    pub top_chatters: Vec<ChatterStat>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmoteProviderInfo {
+    pub id: String,
+    pub name: String,
+    // Emotes of providers with higher priority win name conflicts
+    pub priority: i32,
+    pub enabled: bool,
+    pub capabilities: EmoteProviderCapabilities,
+    pub health: EmoteProviderHealth,
+}
@@ -3,7 +3,147 @@ use std::fmt::Display;
 use bounce::Atom;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TextShadowStyle {
+    None,
+    Shadow,
+    Outline,
+}
+
+// Modifier key which makes locked overlay interactive while held
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InteractModifier {
+    None,
+    Ctrl,
+    Alt,
+    Shift,
+}
+
+// What to do with messages over the rate limit
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RateLimitStrategy {
+    // Show messages until the limit is reached and drop the rest
+    Drop,
+    // Show evenly spread subset of messages
+    Sample,
+}
+
+// Which chat messages are read aloud
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TtsPolicy {
+    All,
+    // Messages highlighted with channel points
+    Highlights,
+    // Messages mentioning the channel owner
+    Mentions,
+    // Messages from subscribers and cheers
+    SubsAndBits,
+}
+
+// How animated emotes are shown
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EmoteAnimation {
+    Animated,
+    Static,
+    // Static until hovered, e.g. in the interactive overlay
+    OnHover,
+}
+
+// Size of emote images, higher scales look sharper on HiDPI screens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EmoteScale {
+    // Picked by the browser from the screen scale factor
+    Auto,
+    X1,
+    X2,
+    X4,
+}
+
+// Global shortcuts in Tauri accelerator format (e.g. `CmdOrCtrl+Alt+L`),
+// empty string disables the shortcut
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub toggle_lock: String,
+    pub toggle_visibility: String,
+    pub toggle_always_on_top: String,
+    pub clear_chat: String,
+    pub next_profile: String,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            toggle_lock: String::from("CmdOrCtrl+Alt+L"),
+            toggle_visibility: String::from("CmdOrCtrl+Alt+H"),
+            toggle_always_on_top: String::from("CmdOrCtrl+Alt+T"),
+            clear_chat: String::from("CmdOrCtrl+Alt+C"),
+            next_profile: String::from("CmdOrCtrl+Alt+P"),
+        }
+    }
+}
+
+// Sound played for a chat event, `sound` is a bundled sound name
+// or a file name from the user sounds directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SoundRule {
+    pub enabled: bool,
+    pub sound: String,
+    // 0.0 - 1.0
+    pub volume: f32,
+}
+
+impl SoundRule {
+    fn new(sound: &str) -> Self {
+        Self {
+            enabled: false,
+            sound: sound.to_string(),
+            volume: 0.5,
+        }
+    }
+}
+
+impl Default for SoundRule {
+    fn default() -> Self {
+        Self::new("chime")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SoundSettings {
+    // Messages mentioning the channel owner
+    pub mention: SoundRule,
+    // First message of a chatter in the channel
+    pub first_message: SoundRule,
+    // Subscriptions, resubscriptions and gifted subscriptions
+    pub subscription: SoundRule,
+    pub raid: SoundRule,
+    // Messages containing any of the `keywords`
+    pub keyword: SoundRule,
+    // Comma separated, case insensitive
+    pub keywords: String,
+    // Minimal time between two sounds of the same rule
+    pub cooldown_secs: u32,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            mention: SoundRule::new("chime"),
+            first_message: SoundRule::new("pop"),
+            subscription: SoundRule::new("fanfare"),
+            raid: SoundRule::new("fanfare"),
+            keyword: SoundRule::new("ping"),
+            keywords: String::new(),
+            cooldown_secs: 5,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Atom, PartialEq)]
+#[serde(default)]
 pub struct AppSettings {
     pub channel_name: String,
     pub always_on_top: bool,
@@ -13,6 +153,48 @@ pub struct AppSettings {
     pub font_size: i32,
     pub enable_animation: bool,
     pub messages_sort_asc: bool,
+    pub theme: String,
+    pub font_family: String,
+    pub font_weight: i32,
+    pub text_shadow_style: TextShadowStyle,
+    pub text_shadow_color: String,
+    pub text_shadow_width: f32,
+    pub line_height: f32,
+    pub message_spacing: i32,
+    pub use_user_name_colors: bool,
+    pub generate_missing_name_colors: bool,
+    pub adjust_name_colors: bool,
+    pub active_profile: String,
+    pub hotkeys: HotkeySettings,
+    pub interact_modifier: InteractModifier,
+    pub interact_hotspot: bool,
+    pub max_messages_per_second: u32,
+    pub rate_limit_strategy: RateLimitStrategy,
+    pub privileged_only: bool,
+    pub min_message_length: u32,
+    pub collapse_emote_spam: bool,
+    pub collapse_duplicates: bool,
+    pub duplicate_window_secs: u32,
+    pub tts_enabled: bool,
+    pub tts_policy: TtsPolicy,
+    pub tts_voice: String,
+    pub tts_rate: u32,
+    pub tts_vary_voices: bool,
+    pub sounds: SoundSettings,
+    pub overlay_server_enabled: bool,
+    pub overlay_server_port: u16,
+    pub control_api_enabled: bool,
+    // Left out of Display, which is used for logging settings
+    pub control_api_token: String,
+    // Profiles shown in additional overlay windows next to the main one
+    pub overlay_windows: Vec<String>,
+    // Ids of emote providers which are not fetched
+    pub disabled_emote_providers: Vec<String>,
+    pub strip_invisible_characters: bool,
+    pub emote_animation: EmoteAnimation,
+    pub emote_scale: EmoteScale,
+    pub show_stats_bar: bool,
+    pub live_emote_updates: bool,
 }
 
 impl AppSettings {
@@ -26,6 +208,45 @@ impl AppSettings {
             font_size: 14,
             enable_animation: true,
             messages_sort_asc: true,
+            theme: String::from("default"),
+            font_family: String::from(""),
+            font_weight: 400,
+            text_shadow_style: TextShadowStyle::Shadow,
+            text_shadow_color: String::from("#000000"),
+            text_shadow_width: 1.0,
+            line_height: 1.5,
+            message_spacing: 3,
+            use_user_name_colors: true,
+            generate_missing_name_colors: true,
+            adjust_name_colors: true,
+            active_profile: String::from(""),
+            hotkeys: HotkeySettings::default(),
+            interact_modifier: InteractModifier::None,
+            interact_hotspot: false,
+            max_messages_per_second: 0,
+            rate_limit_strategy: RateLimitStrategy::Drop,
+            privileged_only: false,
+            min_message_length: 0,
+            collapse_emote_spam: false,
+            collapse_duplicates: true,
+            duplicate_window_secs: 10,
+            tts_enabled: false,
+            tts_policy: TtsPolicy::Mentions,
+            tts_voice: "en".to_string(),
+            tts_rate: 175,
+            tts_vary_voices: true,
+            sounds: SoundSettings::default(),
+            overlay_server_enabled: false,
+            overlay_server_port: 6125,
+            control_api_enabled: false,
+            control_api_token: String::new(),
+            overlay_windows: Vec::new(),
+            disabled_emote_providers: Vec::new(),
+            strip_invisible_characters: true,
+            emote_animation: EmoteAnimation::Animated,
+            emote_scale: EmoteScale::Auto,
+            show_stats_bar: false,
+            live_emote_updates: true,
         }
     }
 }
@@ -48,7 +269,45 @@ impl Display for AppSettings {
                 background_opacity: {},
                 font_size: {},
                 enable_animation: {},
-                messages_sort_asc: {}
+                messages_sort_asc: {},
+                theme: {},
+                font_family: {},
+                font_weight: {},
+                text_shadow_style: {:?},
+                text_shadow_color: {},
+                text_shadow_width: {},
+                line_height: {},
+                message_spacing: {},
+                use_user_name_colors: {},
+                generate_missing_name_colors: {},
+                adjust_name_colors: {},
+                active_profile: {},
+                hotkeys: {:?},
+                interact_modifier: {:?},
+                interact_hotspot: {},
+                max_messages_per_second: {},
+                rate_limit_strategy: {:?},
+                privileged_only: {},
+                min_message_length: {},
+                collapse_emote_spam: {},
+                collapse_duplicates: {},
+                duplicate_window_secs: {},
+                tts_enabled: {},
+                tts_policy: {:?},
+                tts_voice: {},
+                tts_rate: {},
+                tts_vary_voices: {},
+                sounds: {:?},
+                overlay_server_enabled: {},
+                overlay_server_port: {},
+                control_api_enabled: {},
+                overlay_windows: {:?},
+                disabled_emote_providers: {:?},
+                strip_invisible_characters: {},
+                emote_animation: {:?},
+                emote_scale: {:?},
+                show_stats_bar: {},
+                live_emote_updates: {}
             }}",
             self.channel_name,
             self.always_on_top,
@@ -57,7 +316,45 @@ impl Display for AppSettings {
             self.background_opacity,
             self.font_size,
             self.enable_animation,
-            self.messages_sort_asc
+            self.messages_sort_asc,
+            self.theme,
+            self.font_family,
+            self.font_weight,
+            self.text_shadow_style,
+            self.text_shadow_color,
+            self.text_shadow_width,
+            self.line_height,
+            self.message_spacing,
+            self.use_user_name_colors,
+            self.generate_missing_name_colors,
+            self.adjust_name_colors,
+            self.active_profile,
+            self.hotkeys,
+            self.interact_modifier,
+            self.interact_hotspot,
+            self.max_messages_per_second,
+            self.rate_limit_strategy,
+            self.privileged_only,
+            self.min_message_length,
+            self.collapse_emote_spam,
+            self.collapse_duplicates,
+            self.duplicate_window_secs,
+            self.tts_enabled,
+            self.tts_policy,
+            self.tts_voice,
+            self.tts_rate,
+            self.tts_vary_voices,
+            self.sounds,
+            self.overlay_server_enabled,
+            self.overlay_server_port,
+            self.control_api_enabled,
+            self.overlay_windows,
+            self.disabled_emote_providers,
+            self.strip_invisible_characters,
+            self.emote_animation,
+            self.emote_scale,
+            self.show_stats_bar,
+            self.live_emote_updates
         )
     }
 }
@@ -3,21 +3,23 @@ use futures::StreamExt;
 use gigachat_models::AppSettings;
 use log::info;
 use stylist::yew::styled_component;
-use tauri_sys::{event::listen, tauri::invoke};
+use tauri_sys::tauri::invoke;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_hooks::use_effect_once;
 use yew_router::prelude::*;
 
 use crate::{
-    pages::{ChatPage, SettingsPage},
+    pages::{ChatPage, EmotesPage, SettingsPage},
     route::Route,
+    utils::events::{is_browser_source, listen_event},
 };
 
 fn switch_route(route: Route) -> Html {
     match route {
         Route::Chat => html! { <ChatPage /> },
         Route::Settings => html! { <SettingsPage /> },
+        Route::Emotes => html! { <EmotesPage /> },
     }
 }
 
@@ -40,12 +42,14 @@ pub fn app() -> Html {
 
         use_effect_once(move || {
             spawn_local(async move {
-                let mut stream = listen::<AppSettings>("init_settings").await.unwrap();
-                let _: () = invoke("app_ready", &()).await.unwrap();
+                let mut stream = listen_event::<AppSettings>("init_settings").await;
 
-                while let Some(app_settings_event) = stream.next().await {
-                    let app_settings_val = app_settings_event.payload;
+                // Overlay server sends settings to browser sources as soon as they connect
+                if !is_browser_source() {
+                    let _: () = invoke("app_ready", &()).await.unwrap();
+                }
 
+                while let Some(app_settings_val) = stream.next().await {
                     info!("Initializing settings: {:?}", app_settings_val);
 
                     app_settings.set(app_settings_val);
@@ -59,12 +63,10 @@ pub fn app() -> Html {
 
     use_effect_once(move || {
         spawn_local(async move {
-            let mut stream = listen::<AppSettings>("sync_settings").await.unwrap();
-
-            while let Some(new_settings_event) = stream.next().await {
-                info!("sync_settings event: {:?}", new_settings_event);
+            let mut stream = listen_event::<AppSettings>("sync_settings").await;
 
-                let new_settings = new_settings_event.payload;
+            while let Some(new_settings) = stream.next().await {
+                info!("sync_settings event: {:?}", new_settings);
 
                 app_settings.set(new_settings);
             }
@@ -0,0 +1,117 @@
+use std::{cell::Cell, rc::Rc};
+
+use gigachat_models::ChatStats;
+use gloo_net::http::Request;
+use gloo_timers::future::TimeoutFuture;
+use log::error;
+use stylist::yew::styled_component;
+use tauri_sys::tauri::invoke;
+use yew::{
+    classes, html, platform::spawn_local, use_effect_with_deps, use_state, Html, Properties,
+};
+
+use crate::{
+    pages::chat::get_chat_stats_args::GetChatStatsArgs,
+    utils::events::{get_server_path, is_browser_source},
+};
+
+const REFRESH_INTERVAL_MS: u32 = 5000;
+
+async fn load_chat_stats(channel_name: String) -> Option<ChatStats> {
+    if is_browser_source() {
+        let url = get_server_path("/stats.json", "channel", &channel_name);
+
+        return match Request::get(&url).send().await {
+            Ok(response) => response.json().await.ok(),
+            Err(err) => {
+                error!("Failed to load chat stats: {:?}", err);
+                None
+            }
+        };
+    }
+
+    match invoke("get_chat_stats", &GetChatStatsArgs { channel_name }).await {
+        Ok(stats) => Some(stats),
+        Err(err) => {
+            error!("Failed to load chat stats: {:?}", err);
+            None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct ChatStatsBarProps {
+    pub channel_name: String,
+}
+
+// Compact line with chat activity, refreshed periodically
+#[styled_component(ChatStatsBar)]
+pub fn chat_stats_bar(props: &ChatStatsBarProps) -> Html {
+    let stats = use_state(ChatStats::default);
+
+    {
+        let stats = stats.clone();
+
+        use_effect_with_deps(
+            move |channel_name| {
+                let channel_name = channel_name.clone();
+                // Stops polling of the previous channel when the setting changes
+                let is_active = Rc::new(Cell::new(true));
+                let is_polling = is_active.clone();
+
+                spawn_local(async move {
+                    while is_polling.get() {
+                        if let Some(new_stats) = load_chat_stats(channel_name.clone()).await {
+                            if is_polling.get() {
+                                stats.set(new_stats);
+                            }
+                        }
+
+                        TimeoutFuture::new(REFRESH_INTERVAL_MS).await;
+                    }
+                });
+
+                move || is_active.set(false)
+            },
+            props.channel_name.clone(),
+        );
+    }
+
+    let style = css!(
+        r#"
+            display: flex;
+            flex-wrap: wrap;
+            align-items: center;
+            gap: 0.25rem 1rem;
+            padding: 0.25rem 0.5rem;
+            font-size: 0.85em;
+            opacity: 0.85;
+        "#,
+    );
+    let emote_style = css!(
+        r#"
+            display: inline-flex;
+            align-items: center;
+            gap: 0.25rem;
+
+            & img {
+                height: 1.5em;
+            }
+        "#,
+    );
+
+    html! {
+        <div class={classes!("gc-stats-bar", style)}>
+            <span class="gc-stats-rate">{format!("{} msg/min", stats.messages_per_minute)}</span>
+            <span class="gc-stats-chatters">{format!("{} chatters", stats.unique_chatters)}</span>
+            {
+                for stats.top_emotes.iter().map(|emote| html! {
+                    <span class={classes!("gc-stats-emote", emote_style.clone())} title={emote.code.clone()}>
+                        <img src={emote.url.clone()} alt={emote.code.clone()} />
+                        {format!("×{}", emote.count)}
+                    </span>
+                })
+            }
+        </div>
+    }
+}
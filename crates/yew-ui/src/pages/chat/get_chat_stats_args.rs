@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct GetChatStatsArgs {
+    #[serde(rename = "channelName")]
+    pub channel_name: String,
+}
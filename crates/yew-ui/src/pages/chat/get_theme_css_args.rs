@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct GetThemeCssArgs {
+    #[serde(rename = "themeName")]
+    pub theme_name: String,
+}
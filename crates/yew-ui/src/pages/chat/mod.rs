@@ -1,20 +1,53 @@
 use bounce::use_atom_value;
 use futures::StreamExt;
-use gigachat_models::{AppSettings, UpdateIgnoreEventsArgs};
-use log::info;
+use gigachat_models::{AppSettings, UpdateIgnoreEventsArgs, UpdateInteractionEventArgs};
+use gloo_net::http::Request;
+use log::{error, info};
 use stylist::yew::styled_component;
-use tauri_sys::{event::listen, tauri::invoke};
-use yew::{html, platform::spawn_local, use_callback, use_state, Html, UseStateHandle};
-use yew_hooks::use_effect_once;
+use tauri_sys::tauri::invoke;
+use yew::{
+    classes, html, platform::spawn_local, use_callback, use_effect_with_deps, use_state, Html,
+    UseStateHandle,
+};
+use yew_hooks::{use_effect_once, use_latest};
 
 use crate::{
     components::layout::drag_overlay::DragOverlay,
-    pages::chat::twitch_messages_list::TwitchMessagesList, utils::colors::hex_to_rgb,
+    pages::chat::{
+        chat_stats_bar::ChatStatsBar, get_theme_css_args::GetThemeCssArgs,
+        twitch_messages_list::TwitchMessagesList,
+    },
+    utils::{
+        colors::hex_to_rgb,
+        events::{get_server_path, is_browser_source, listen_event},
+        fonts::font_family_css,
+    },
 };
 
+mod chat_stats_bar;
+mod get_chat_stats_args;
+mod get_theme_css_args;
 mod twitch_chat_message;
 pub mod twitch_messages_list;
 
+async fn load_theme_css(theme_name: String) -> String {
+    if is_browser_source() {
+        let url = get_server_path("/theme.css", "theme", &theme_name);
+
+        return match Request::get(&url).send().await {
+            Ok(response) => response.text().await.unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to load theme css: {:?}", err);
+                String::new()
+            }
+        };
+    }
+
+    invoke("get_theme_css", &GetThemeCssArgs { theme_name })
+        .await
+        .unwrap()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 struct SaveAlwaysOnTopDeps {
     always_on_top: UseStateHandle<bool>,
@@ -25,18 +58,53 @@ struct SaveAlwaysOnTopDeps {
 pub fn chat_page() -> Html {
     let app_settings = use_atom_value::<AppSettings>();
     let is_ui_locked = use_state(|| true);
+    let is_interactive = use_state(|| false);
+    let theme_css = use_state(String::new);
+    let latest_theme_name = use_latest(app_settings.theme.clone());
+
+    {
+        let theme_css = theme_css.clone();
+
+        use_effect_with_deps(
+            move |theme_name| {
+                let theme_name = theme_name.clone();
+
+                spawn_local(async move {
+                    theme_css.set(load_theme_css(theme_name).await);
+                });
+            },
+            app_settings.theme.clone(),
+        );
+    }
+
+    {
+        // Hot reload user theme file
+        let theme_css = theme_css.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let mut stream = listen_event::<()>("theme_updated").await;
+
+                while stream.next().await.is_some() {
+                    let theme_name = (*latest_theme_name.current()).clone();
+                    info!("Reloading theme: {}", theme_name);
+
+                    theme_css.set(load_theme_css(theme_name).await);
+                }
+            });
+
+            || {}
+        });
+    }
 
     {
         let is_ui_locked = is_ui_locked.clone();
 
         use_effect_once(move || {
             spawn_local(async move {
-                let mut stream = listen::<UpdateIgnoreEventsArgs>("toggle_ui_lock")
-                    .await
-                    .unwrap();
+                let mut stream = listen_event::<UpdateIgnoreEventsArgs>("toggle_ui_lock").await;
 
-                while let Some(ui_locked_event) = stream.next().await {
-                    let ui_locked_value = ui_locked_event.payload;
+                while let Some(ui_locked_value) = stream.next().await {
                     is_ui_locked.set(ui_locked_value.ignore_events);
 
                     info!("Updating ignore events settings: {:?}", ui_locked_value);
@@ -47,6 +115,23 @@ pub fn chat_page() -> Html {
         });
     }
 
+    {
+        let is_interactive = is_interactive.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let mut stream =
+                    listen_event::<UpdateInteractionEventArgs>("toggle_interaction").await;
+
+                while let Some(interaction) = stream.next().await {
+                    is_interactive.set(interaction.interactive);
+                }
+            });
+
+            || {}
+        });
+    }
+
     let unlock_ui = {
         let is_ui_locked = is_ui_locked.clone();
 
@@ -64,14 +149,32 @@ pub fn chat_page() -> Html {
     };
 
     let background_rgb = hex_to_rgb(&app_settings.background_color[1..]);
+    let font_family = font_family_css(&app_settings.font_family);
     let style = css! {
-        min-height: 100%;
+        display: flex;
+        flex-direction: column;
+        height: 100%;
         font-size: ${app_settings.font_size}px;
+        font-family: ${font_family};
+        font-weight: ${app_settings.font_weight};
+        line-height: ${app_settings.line_height};
         background: rgba(${background_rgb.0}, ${background_rgb.1}, ${background_rgb.2}, ${app_settings.background_opacity});
     };
+    let hotspot_style = css! {
+        position: fixed;
+        top: 0;
+        right: 0;
+        width: 24px;
+        height: 24px;
+        border-top: 2px solid rgba(139, 80, 255, 0.6);
+        border-right: 2px solid rgba(139, 80, 255, 0.6);
+        z-index: 10;
+    };
 
     html! {
-        <div class={style}>
+        <div class={classes!("gc-chat", style)}>
+            <style>{(*theme_css).clone()}</style>
+
             {
                 if !*is_ui_locked {
                     html!{<DragOverlay on_done={unlock_ui.clone()} />}
@@ -81,7 +184,27 @@ pub fn chat_page() -> Html {
                 }
             }
 
-            <TwitchMessagesList sort_asc={app_settings.messages_sort_asc} />
+            {
+                if app_settings.interact_hotspot && *is_ui_locked {
+                    html!{<div class={classes!("gc-interact-hotspot", hotspot_style)}></div>}
+                }
+                else {
+                    html!{}
+                }
+            }
+
+            {
+                if app_settings.show_stats_bar {
+                    html!{<ChatStatsBar channel_name={app_settings.channel_name.clone()} />}
+                }
+                else {
+                    html!{}
+                }
+            }
+
+            <TwitchMessagesList
+                sort_asc={app_settings.messages_sort_asc}
+                scrollable={!*is_ui_locked || *is_interactive} />
         </div>
     }
 }
@@ -3,6 +3,11 @@ use chrono::{DateTime, Local, Utc};
 use gigachat_models::AppSettings;
 use stylist::yew::use_style;
 
+use crate::utils::{
+    colors::{resolve_name_color, NameColorOptions},
+    text_shadow::text_shadow_css,
+};
+
 use yew::{
     classes, function_component, html, use_effect_with_deps, use_state, AttrValue, Html, Properties,
 };
@@ -10,10 +15,16 @@ use yew::{
 #[derive(Debug, PartialEq, Properties)]
 pub struct TwitchChatMessageProps {
     pub sender_name: AttrValue,
-    pub sender_color: AttrValue,
+    pub sender_login: AttrValue,
+    pub sender_color: Option<String>,
     pub message: AttrValue,
     pub message_id: AttrValue,
     pub timestamp: chrono::DateTime<Utc>,
+    #[prop_or(1)]
+    pub repeat_count: u32,
+    // Shown without a sender, e.g. live emote updates
+    #[prop_or(false)]
+    pub system: bool,
 
     #[prop_or(false)]
     pub show_timestamp: bool,
@@ -29,7 +40,7 @@ fn twitch_chat_timestamp(props: &TwitchChatTimestampProps) -> Html {
     let timestamp_local: DateTime<Local> = chrono::DateTime::from(props.timestamp);
 
     html! {
-        <span>
+        <span class="gc-timestamp">
             {timestamp_local.format("%H:%M")}
         </span>
     }
@@ -37,10 +48,17 @@ fn twitch_chat_timestamp(props: &TwitchChatTimestampProps) -> Html {
 
 #[function_component(TwitchChatMessage)]
 pub fn twitch_chat_message(props: &TwitchChatMessageProps) -> Html {
+    let app_settings = use_atom_value::<AppSettings>();
+    let text_shadow = text_shadow_css(
+        app_settings.text_shadow_style,
+        &app_settings.text_shadow_color,
+        app_settings.text_shadow_width,
+    );
+
     let container_style = use_style! {
         position: relative;
         left: 0;
-        padding: 0.2rem;
+        padding: ${app_settings.message_spacing}px 0.2rem;
         transition: 300ms;
         opacity: 1;
 
@@ -54,20 +72,60 @@ pub fn twitch_chat_message(props: &TwitchChatMessageProps) -> Html {
             margin: -0.2rem 0;
         }
 
-        .sender {
+        &.gc-system {
+            font-style: italic;
+        }
+
+        .gc-sender {
             font-weight: bold;
         }
 
-        .message {
-            text-shadow: 1px 1px 0 #000;
+        .gc-message-body {
+            text-shadow: ${text_shadow};
+        }
+
+        .gc-text {
+            white-space: pre-wrap;
+        }
+
+        .gc-emote img {
+            height: ${app_settings.font_size * 2}px;
+        }
+
+        .gc-emote-hover .gc-emote-animated,
+        .gc-emote-hover:hover .gc-emote-static {
+            display: none;
+        }
+
+        .gc-emote-hover:hover .gc-emote-animated {
+            display: inline;
+        }
+
+        .gc-repeat-count {
+            margin-left: 4px;
+            padding: 0 5px;
+            border-radius: 8px;
+            background: rgba(255, 255, 255, 0.15);
+            font-size: 0.85rem;
+            font-weight: bold;
         }
     };
 
+    let sender_color = resolve_name_color(
+        props.sender_color.as_deref(),
+        &props.sender_login,
+        &NameColorOptions {
+            use_user_colors: app_settings.use_user_name_colors,
+            generate_missing_colors: app_settings.generate_missing_name_colors,
+            adjust_for_contrast: app_settings.adjust_name_colors,
+            background_color: app_settings.background_color.clone(),
+            background_opacity: app_settings.background_opacity,
+        },
+    );
     let sender_color_style = use_style!(
-        color: ${props.sender_color.clone()};
+        color: ${sender_color};
     );
     let init_class_name = use_state(|| "appear-right-transition");
-    let app_settings = use_atom_value::<AppSettings>();
 
     {
         let init_class_name = init_class_name.clone();
@@ -96,7 +154,7 @@ pub fn twitch_chat_message(props: &TwitchChatMessageProps) -> Html {
     }
 
     html! {
-        <div class={classes!(container_style, *init_class_name.clone())} id={props.message_id.clone()}>
+        <div class={classes!("gc-message", props.system.then_some("gc-system"), container_style, *init_class_name.clone())} id={props.message_id.clone()}>
             {
                 if props.show_timestamp {
                     html! { <TwitchChatTimestamp timestamp={props.timestamp} /> }
@@ -107,11 +165,30 @@ pub fn twitch_chat_message(props: &TwitchChatMessageProps) -> Html {
             }
 
             {" "}
-            <span class={classes!(sender_color_style, "sender")}>{ props.sender_name.clone() }</span>
-            {": "}
-            <span class="message">
+            {
+                if props.system {
+                    html!{}
+                }
+                else {
+                    html! {
+                        <>
+                            <span class={classes!(sender_color_style, "gc-sender")}>{ props.sender_name.clone() }</span>
+                            <span class="gc-separator">{": "}</span>
+                        </>
+                    }
+                }
+            }
+            <span class="gc-message-body">
                 { Html::from_html_unchecked(props.message.clone()) }
             </span>
+            {
+                if props.repeat_count > 1 {
+                    html! { <span class="gc-repeat-count">{format!("\u{d7}{}", props.repeat_count)}</span> }
+                }
+                else {
+                    html!{}
+                }
+            }
         </div>
     }
 }
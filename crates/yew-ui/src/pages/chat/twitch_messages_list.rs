@@ -1,81 +1,171 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use futures::StreamExt;
-use gigachat_models::{GigaChatMessage, GigaChatSender};
+use gigachat_models::{GigaChatMessage, GigaChatSender, UpdateMessageEventArgs};
 use stylist::yew::styled_component;
-use tauri_sys::event::listen;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{HtmlElement, ScrollBehavior, ScrollIntoViewOptions};
+use web_sys::{Element, HtmlElement, ScrollBehavior, ScrollIntoViewOptions};
 use yew::{
-    html, use_effect_with_deps, use_node_ref, use_state, Html, NodeRef, Properties, UseStateHandle,
+    classes, html, use_effect, use_effect_with_deps, use_force_update, use_mut_ref, use_node_ref,
+    use_state, use_state_eq, Callback, Event, Html, NodeRef, Properties,
 };
-use yew_hooks::{use_effect_once, use_latest};
+use yew_hooks::use_effect_once;
 
-use crate::pages::chat::twitch_chat_message::TwitchChatMessage;
+use crate::{
+    pages::chat::twitch_chat_message::TwitchChatMessage,
+    utils::{events::listen_event, virtual_list::get_visible_range},
+};
+
+// Distance from the newest message edge at which auto scroll is resumed
+const AUTO_SCROLL_RESUME_DISTANCE: i32 = 30;
+
+const MESSAGES_CAPACITY: usize = 50;
+const PAUSED_MESSAGES_CAPACITY: usize = 500;
+
+// Height used for rows which were not rendered yet
+const ESTIMATED_ROW_HEIGHT: f64 = 28_f64;
+const FALLBACK_VIEWPORT_HEIGHT: f64 = 600_f64;
+// Rows rendered outside of the viewport to avoid blank areas while scrolling
+const OVERSCAN_ROWS: usize = 10;
 
 #[derive(Clone, Debug)]
 struct MessagesQueue {
     capacity: usize,
+    // Larger capacity used while auto scroll is paused,
+    // so messages are not evicted while someone is reading them
+    paused_capacity: usize,
     sort_asc: bool,
+    paused: bool,
+    unseen_count: usize,
+    // Incremented on every change, used to react to changes without comparing messages
+    revision: usize,
     data: VecDeque<GigaChatMessage>,
 }
 
 impl MessagesQueue {
-    fn new(capacity: usize) -> Self {
+    fn new(capacity: usize, paused_capacity: usize) -> Self {
         Self {
             capacity,
+            paused_capacity,
             sort_asc: false,
+            paused: false,
+            unseen_count: 0,
+            revision: 0,
             data: VecDeque::with_capacity(capacity),
         }
     }
 
-    fn push(&mut self, message: GigaChatMessage) {
-        if self.data.len() == self.capacity - 1 {
+    fn get_capacity(&self) -> usize {
+        if self.paused {
+            self.paused_capacity
+        } else {
+            self.capacity
+        }
+    }
+
+    // Removes the oldest messages which do not fit into current capacity
+    fn trim(&mut self) {
+        while self.data.len() > self.get_capacity() {
             if self.sort_asc {
-                self.data.remove(0);
+                self.data.pop_front();
             } else {
                 self.data.pop_back();
             }
         }
+    }
 
+    fn push(&mut self, message: GigaChatMessage) {
         if self.sort_asc {
             self.data.push_back(message);
         } else {
             self.data.push_front(message);
         }
+
+        if self.paused {
+            self.unseen_count += 1;
+        }
+
+        self.trim();
+        self.revision += 1;
+    }
+
+    fn push_batch(&mut self, messages: Vec<GigaChatMessage>) {
+        for message in messages {
+            self.push(message);
+        }
+    }
+
+    fn update_repeat_count(&mut self, update: &UpdateMessageEventArgs) {
+        if let Some(message) = self
+            .data
+            .iter_mut()
+            .find(|message| message.message_id == update.message_id)
+        {
+            message.repeat_count = update.repeat_count;
+            self.revision += 1;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.data.clear();
+        self.unseen_count = 0;
+        self.revision += 1;
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused {
+            return;
+        }
+
+        self.paused = paused;
+
+        if !paused {
+            self.unseen_count = 0;
+        }
+
+        self.trim();
+        self.revision += 1;
     }
 
     fn set_sort(&mut self, sort_asc: bool) {
-        self.sort_asc = sort_asc;
+        if self.sort_asc == sort_asc {
+            return;
+        }
 
-        self.data = self.data.clone().into_iter().rev().collect();
+        self.sort_asc = sort_asc;
+        self.data.make_contiguous().reverse();
+        self.revision += 1;
     }
 }
 
 impl Default for MessagesQueue {
     fn default() -> Self {
-        Self::new(10)
+        Self::new(10, 100)
     }
 }
 
-impl PartialEq for MessagesQueue {
-    fn eq(&self, other: &Self) -> bool {
-        self.data == other.data
-    }
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Viewport {
+    scroll_top: i32,
+    height: i32,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 struct ScrollListDependents {
     scroll_to_last_ref: NodeRef,
     scroll_to_first_ref: NodeRef,
-    messages: UseStateHandle<MessagesQueue>,
+    revision: usize,
     scroll_asc: bool,
+    auto_scroll: bool,
 }
 
 #[derive(Clone, Debug, Properties, PartialEq)]
 pub struct TwitchMessagesListProps {
     #[prop_or(true)]
     pub sort_asc: bool,
+    #[prop_or(false)]
+    pub scrollable: bool,
 }
 
 #[styled_component(TwitchMessagesList)]
@@ -85,7 +175,7 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             GigaChatSender::new(
                 "test_login".to_string(),
                 "test_display_name".to_string(),
-                "#999999".to_string(),
+                Some("#999999".to_string()),
             ),
             "message1".to_string(),
             "<span>message1</span>".to_string(),
@@ -96,7 +186,7 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             GigaChatSender::new(
                 "test_login".to_string(),
                 "test_display_name2".to_string(),
-                "#999999".to_string(),
+                Some("#999999".to_string()),
             ),
             "message2".to_string(),
             "<span>message2</span>".to_string(),
@@ -107,7 +197,7 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             GigaChatSender::new(
                 "test_login2".to_string(),
                 "test_display_name3".to_string(),
-                "red".to_string(),
+                None,
             ),
             "message3".to_string(),
             "<span>message3</span>".to_string(),
@@ -118,7 +208,7 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             GigaChatSender::new(
                 "test_login2".to_string(),
                 "test_display_name4".to_string(),
-                "red".to_string(),
+                None,
             ),
             "message4".to_string(),
             "<span>message4</span>".to_string(),
@@ -126,29 +216,72 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             chrono::DateTime::<chrono::Utc>::default(),
         ),
     ];
-    let mut init_queue = MessagesQueue::new(50);
+    let mut init_queue = MessagesQueue::new(MESSAGES_CAPACITY, PAUSED_MESSAGES_CAPACITY);
     for message in init_messages {
         init_queue.push(message);
     }
-    let messages: UseStateHandle<MessagesQueue> = use_state(|| init_queue);
+    // Queue is mutated in place and re-render is triggered manually,
+    // so busy chats don't clone all messages on every batch
+    let messages = use_mut_ref(|| init_queue);
+    let force_update = use_force_update();
+    let row_heights = use_mut_ref(HashMap::<String, f64>::new);
+    let viewport = use_state_eq(Viewport::default);
     let scroll_to_last_ref = use_node_ref();
     let scroll_to_first_ref = use_node_ref();
-    let latest_messages = use_latest(messages.clone());
+    let list_ref = use_node_ref();
+    let rows_ref = use_node_ref();
+    let auto_scroll = use_state(|| true);
+    let last_scroll_top = use_mut_ref(|| 0);
 
     {
         let messages = messages.clone();
-        let latest_messages = latest_messages.clone();
+        let force_update = force_update.clone();
 
         use_effect_once(move || {
             spawn_local(async move {
-                let mut stream = listen::<GigaChatMessage>("new_message").await.unwrap();
-                while let Some(new_message_event) = stream.next().await {
-                    let message = new_message_event.payload;
+                let mut stream = listen_event::<Vec<GigaChatMessage>>("new_messages").await;
+                while let Some(new_messages) = stream.next().await {
+                    messages.borrow_mut().push_batch(new_messages);
+
+                    force_update.force_update();
+                }
+            });
+
+            || {}
+        });
+    }
 
-                    let mut messages_cloned = (**latest_messages.current()).clone();
-                    messages_cloned.push(message);
+    {
+        let messages = messages.clone();
+        let force_update = force_update.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let mut stream = listen_event::<UpdateMessageEventArgs>("message_updated").await;
+                while let Some(message_update) = stream.next().await {
+                    messages.borrow_mut().update_repeat_count(&message_update);
 
-                    messages.set(messages_cloned);
+                    force_update.force_update();
+                }
+            });
+
+            || {}
+        });
+    }
+
+    {
+        let messages = messages.clone();
+        let row_heights = row_heights.clone();
+        let force_update = force_update.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let mut stream = listen_event::<()>("clear_chat").await;
+                while stream.next().await.is_some() {
+                    messages.borrow_mut().clear();
+                    row_heights.borrow_mut().clear();
+
+                    force_update.force_update();
                 }
             });
 
@@ -159,19 +292,100 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
     {
         // Update sort
         let messages = messages.clone();
-        let latest_messages = latest_messages;
+        let force_update = force_update.clone();
 
         use_effect_with_deps(
             move |sort_asc| {
-                let mut messages_cloned = (**latest_messages.current()).clone();
-                messages_cloned.set_sort(*sort_asc);
+                messages.borrow_mut().set_sort(*sort_asc);
 
-                messages.set(messages_cloned);
+                force_update.force_update();
             },
             props.sort_asc,
         );
     }
 
+    {
+        // Keep older messages and count new ones while auto scroll is paused
+        let messages = messages.clone();
+        let force_update = force_update.clone();
+
+        use_effect_with_deps(
+            move |auto_scroll| {
+                messages.borrow_mut().set_paused(!*auto_scroll);
+
+                force_update.force_update();
+            },
+            *auto_scroll,
+        );
+    }
+
+    {
+        // Measure rendered rows, so that not rendered ones are replaced with correctly sized spacers
+        let messages = messages.clone();
+        let row_heights = row_heights.clone();
+        let rows_ref = rows_ref.clone();
+        let list_ref = list_ref.clone();
+        let viewport = viewport.clone();
+
+        use_effect(move || {
+            let mut heights_changed = false;
+
+            if let Some(rows) = rows_ref.cast::<Element>() {
+                let mut row_heights = row_heights.borrow_mut();
+                let children = rows.children();
+
+                for index in 0..children.length() {
+                    if let Some(row) = children.item(index) {
+                        let height = row.unchecked_ref::<HtmlElement>().offset_height() as f64;
+                        let previous_height = row_heights.insert(row.id(), height);
+
+                        heights_changed |= previous_height
+                            .map_or(true, |previous| (previous - height).abs() > 0.5);
+                    }
+                }
+
+                // Forget evicted messages
+                let messages = messages.borrow();
+                if row_heights.len() > messages.data.len() * 2 {
+                    let message_ids: HashSet<&str> = messages
+                        .data
+                        .iter()
+                        .map(|message| message.message_id.as_str())
+                        .collect();
+
+                    row_heights.retain(|message_id, _| message_ids.contains(message_id.as_str()));
+                }
+            }
+
+            if let Some(list) = list_ref.cast::<HtmlElement>() {
+                viewport.set(Viewport {
+                    scroll_top: list.scroll_top(),
+                    height: list.client_height(),
+                });
+            }
+
+            if heights_changed {
+                force_update.force_update();
+            }
+
+            || {}
+        });
+    }
+
+    {
+        // Jump back to the newest messages once overlay stops being scrollable
+        let auto_scroll = auto_scroll.clone();
+
+        use_effect_with_deps(
+            move |scrollable| {
+                if !*scrollable {
+                    auto_scroll.set(true);
+                }
+            },
+            props.scrollable,
+        );
+    }
+
     {
         // Scroll to latest message
         let scroll_to_last_ref = scroll_to_last_ref.clone();
@@ -179,6 +393,10 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
 
         use_effect_with_deps(
             move |scroll_list_deps| {
+                if !scroll_list_deps.auto_scroll {
+                    return;
+                }
+
                 let mut scroll_options = ScrollIntoViewOptions::default();
                 scroll_options.behavior(ScrollBehavior::Smooth);
 
@@ -199,36 +417,309 @@ pub fn twitch_messages_list(props: &TwitchMessagesListProps) -> Html {
             ScrollListDependents {
                 scroll_to_last_ref,
                 scroll_to_first_ref,
-                messages: messages.clone(),
+                revision: messages.borrow().revision,
                 scroll_asc: props.sort_asc,
+                auto_scroll: *auto_scroll,
             },
         );
     }
 
+    let on_scroll = {
+        let list_ref = list_ref.clone();
+        let auto_scroll = auto_scroll.clone();
+        let viewport = viewport.clone();
+        let sort_asc = props.sort_asc;
+
+        Callback::from(move |_: Event| {
+            let list = match list_ref.cast::<HtmlElement>() {
+                Some(list) => list,
+                None => return,
+            };
+
+            let scroll_top = list.scroll_top();
+            let previous_scroll_top = last_scroll_top.replace(scroll_top);
+
+            viewport.set(Viewport {
+                scroll_top,
+                height: list.client_height(),
+            });
+
+            // Newest messages are at the bottom for ascending sort and at the top otherwise
+            let (distance_to_edge, moved_away) = if sort_asc {
+                (
+                    list.scroll_height() - list.client_height() - scroll_top,
+                    scroll_top < previous_scroll_top,
+                )
+            } else {
+                (scroll_top, scroll_top > previous_scroll_top)
+            };
+
+            if distance_to_edge <= AUTO_SCROLL_RESUME_DISTANCE {
+                if !*auto_scroll {
+                    auto_scroll.set(true);
+                }
+            } else if moved_away && *auto_scroll {
+                auto_scroll.set(false);
+            }
+        })
+    };
+
+    let jump_to_latest = {
+        let auto_scroll = auto_scroll.clone();
+
+        Callback::from(move |_| auto_scroll.set(true))
+    };
+
+    let wrapper_style = css!(
+        r#"
+            position: relative;
+            display: flex;
+            flex-direction: column;
+            flex: 1 1 auto;
+            min-height: 0;
+        "#,
+    );
+
+    let new_messages_style = css!(
+        r#"
+            position: absolute;
+            left: 50%;
+            transform: translateX(-50%);
+            z-index: 5;
+            padding: 2px 12px;
+            border: none;
+            border-radius: 12px;
+            background: rgba(139, 80, 255, 0.9);
+            color: #ffffff;
+            font-size: 0.85em;
+            cursor: pointer;
+
+            &.bottom {
+                bottom: 8px;
+            }
+
+            &.top {
+                top: 8px;
+            }
+        "#,
+    );
+
     let container_style = css!(
         r#"
             background: transparent;
             padding: 5px 3px;
+            flex: 1 1 auto;
+            min-height: 0;
+            overflow-y: auto;
+            scrollbar-width: none;
+
+            &::-webkit-scrollbar {
+                display: none;
+            }
+
+            &.scrollable {
+                scrollbar-width: thin;
+            }
+
+            &.scrollable::-webkit-scrollbar {
+                display: block;
+                width: 6px;
+            }
+
+            &.scrollable::-webkit-scrollbar-thumb {
+                background: rgba(255, 255, 255, 0.3);
+                border-radius: 3px;
+            }
         "#,
     );
 
+    let messages = messages.borrow();
+
+    // While following new messages only the newest rows are rendered,
+    // otherwise rows around current scroll position
+    let heights: Vec<f64> = {
+        let row_heights = row_heights.borrow();
+
+        messages
+            .data
+            .iter()
+            .map(|message| {
+                row_heights
+                    .get(&message.message_id)
+                    .copied()
+                    .unwrap_or(ESTIMATED_ROW_HEIGHT)
+            })
+            .collect()
+    };
+    let viewport_height = if viewport.height > 0 {
+        viewport.height as f64
+    } else {
+        FALLBACK_VIEWPORT_HEIGHT
+    };
+    let scroll_top = match (*auto_scroll, props.sort_asc) {
+        (true, true) => f64::MAX,
+        (true, false) => 0_f64,
+        (false, _) => viewport.scroll_top as f64,
+    };
+    let visible_range = get_visible_range(&heights, scroll_top, viewport_height, OVERSCAN_ROWS);
+
+    let new_messages_indicator = if !*auto_scroll && messages.unseen_count > 0 {
+        let position = if props.sort_asc { "bottom" } else { "top" };
+        let label = if messages.unseen_count == 1 {
+            "1 new message".to_string()
+        } else {
+            format!("{} new messages", messages.unseen_count)
+        };
+
+        html! {
+            <button
+                class={classes!("gc-new-messages", new_messages_style, position)}
+                onclick={jump_to_latest}>
+                {format!("{} \u{2014} jump to latest", label)}
+            </button>
+        }
+    } else {
+        html! {}
+    };
+
     html! {
-        <div class={container_style}>
-            <div ref={scroll_to_first_ref}></div>
-
-            {
-                messages.data.iter().map(|message| html! {
-                    <TwitchChatMessage
-                        key={message.message_id.clone()}
-                        sender_name={message.sender.display_name.clone()}
-                        sender_color={message.sender.color.clone()}
-                        message={message.message_html.clone()}
-                        message_id={message.message_id.clone()}
-                        timestamp={message.timestamp} />
-                }).collect::<Html>()
-            }
+        <div class={wrapper_style}>
+            {new_messages_indicator}
+
+            <div
+                ref={list_ref}
+                class={classes!("gc-messages", container_style, props.scrollable.then_some("scrollable"))}
+                onscroll={on_scroll}>
+                <div ref={scroll_to_first_ref}></div>
+                <div style={format!("height: {}px", visible_range.offset_before)}></div>
+
+                <div ref={rows_ref}>
+                    {
+                        messages.data.range(visible_range.start..visible_range.end).map(|message| html! {
+                            <TwitchChatMessage
+                                key={message.message_id.clone()}
+                                sender_name={message.sender.display_name.clone()}
+                                sender_login={message.sender.login.clone()}
+                                sender_color={message.sender.color.clone()}
+                                message={message.message_html.clone()}
+                                message_id={message.message_id.clone()}
+                                repeat_count={message.repeat_count}
+                                system={message.system}
+                                timestamp={message.timestamp} />
+                        }).collect::<Html>()
+                    }
+                </div>
 
-            <div ref={scroll_to_last_ref}></div>
+                <div style={format!("height: {}px", visible_range.offset_after)}></div>
+                <div ref={scroll_to_last_ref}></div>
+            </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_message(id: usize) -> GigaChatMessage {
+        GigaChatMessage::new(
+            GigaChatSender::new("login".to_string(), "name".to_string(), None),
+            format!("message{}", id),
+            format!("<span>message{}</span>", id),
+            format!("message{}_id", id),
+            chrono::DateTime::<chrono::Utc>::default(),
+        )
+    }
+
+    fn get_ids(queue: &MessagesQueue) -> Vec<String> {
+        queue
+            .data
+            .iter()
+            .map(|message| message.message_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_push_evicts_oldest_message() {
+        let mut queue = MessagesQueue::new(2, 4);
+        queue.set_sort(true);
+
+        for id in 0..3 {
+            queue.push(create_message(id));
+        }
+
+        assert_eq!(get_ids(&queue), vec!["message1_id", "message2_id"]);
+    }
+
+    #[test]
+    fn test_paused_queue_keeps_messages_and_counts_unseen() {
+        let mut queue = MessagesQueue::new(2, 4);
+        queue.set_sort(true);
+        queue.push(create_message(0));
+        queue.set_paused(true);
+
+        for id in 1..4 {
+            queue.push(create_message(id));
+        }
+
+        assert_eq!(queue.data.len(), 4);
+        assert_eq!(queue.unseen_count, 3);
+
+        queue.set_paused(false);
+
+        assert_eq!(get_ids(&queue), vec!["message2_id", "message3_id"]);
+        assert_eq!(queue.unseen_count, 0);
+    }
+
+    #[test]
+    fn test_desc_queue_evicts_from_back() {
+        let mut queue = MessagesQueue::new(2, 4);
+
+        for id in 0..3 {
+            queue.push(create_message(id));
+        }
+
+        assert_eq!(get_ids(&queue), vec!["message2_id", "message1_id"]);
+    }
+
+    #[test]
+    fn test_update_repeat_count() {
+        let mut queue = MessagesQueue::new(5, 10);
+        queue.push_batch((0..3).map(create_message).collect());
+
+        queue.update_repeat_count(&UpdateMessageEventArgs {
+            message_id: "message1_id".to_string(),
+            repeat_count: 4,
+        });
+
+        let repeat_counts: Vec<u32> = queue
+            .data
+            .iter()
+            .map(|message| message.repeat_count)
+            .collect();
+        assert_eq!(repeat_counts, vec![1, 4, 1]);
+    }
+
+    #[test]
+    fn test_set_same_sort_keeps_order() {
+        let mut queue = MessagesQueue::new(5, 10);
+        queue.push_batch((0..3).map(create_message).collect());
+        let revision = queue.revision;
+
+        queue.set_sort(false);
+
+        assert_eq!(
+            get_ids(&queue),
+            vec!["message2_id", "message1_id", "message0_id"]
+        );
+        assert_eq!(queue.revision, revision);
+
+        queue.set_sort(true);
+
+        assert_eq!(
+            get_ids(&queue),
+            vec!["message0_id", "message1_id", "message2_id"]
+        );
+        assert!(queue.revision > revision);
+    }
+}
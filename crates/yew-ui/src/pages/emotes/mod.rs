@@ -0,0 +1,179 @@
+mod copy_to_clipboard_args;
+
+use gigachat_models::{EmoteInfo, EmotesGroup};
+use stylist::yew::styled_component;
+use tauri_sys::tauri::invoke;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement};
+use yew::{platform::spawn_local, prelude::*};
+use yew_hooks::use_effect_once;
+
+use copy_to_clipboard_args::CopyToClipboardArgs;
+
+fn matches_search(emote: &EmoteInfo, search: &str) -> bool {
+    emote
+        .code
+        .to_lowercase()
+        .contains(&search.trim().to_lowercase())
+}
+
+#[styled_component(EmotesPage)]
+pub fn emotes_page() -> Html {
+    let emotes_groups = use_state(Vec::<EmotesGroup>::new);
+    let search = use_state(String::new);
+    let copied_code = use_state(|| Option::<String>::None);
+
+    {
+        let emotes_groups = emotes_groups.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let groups: Vec<EmotesGroup> = invoke("get_emotes", &()).await.unwrap();
+                emotes_groups.set(groups);
+            });
+
+            || {}
+        });
+    }
+
+    let on_search_input = {
+        let search = search.clone();
+
+        Callback::from(move |e: InputEvent| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            search.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let get_copy_callback = |code: String| {
+        let copied_code = copied_code.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let code = code.clone();
+            copied_code.set(Some(code.clone()));
+
+            spawn_local(async move {
+                let _: () = invoke("copy_to_clipboard", &CopyToClipboardArgs { text: code })
+                    .await
+                    .unwrap();
+            });
+        })
+    };
+
+    let style = css! {
+        height: 100%;
+        overflow-y: auto;
+        padding: 20px 15px 20px;
+        background: #3c3c3c;
+        color: #fafafa;
+
+        .gc-emotes-grid {
+            display: flex;
+            flex-wrap: wrap;
+            gap: 4px;
+        }
+
+        .gc-emote-tile {
+            display: flex;
+            flex-direction: column;
+            align-items: center;
+            width: 84px;
+            padding: 6px 2px;
+            border: none;
+            border-radius: 4px;
+            background: transparent;
+            color: inherit;
+        }
+
+        .gc-emote-tile:hover {
+            background: rgba(255, 255, 255, 0.1);
+        }
+
+        .gc-emote-tile img {
+            height: 56px;
+            max-width: 80px;
+            object-fit: contain;
+        }
+
+        .gc-emote-code {
+            width: 100%;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+            font-size: 0.75rem;
+        }
+    };
+
+    let groups_html = emotes_groups
+        .iter()
+        .map(|group| {
+            let emotes: Vec<&EmoteInfo> = group
+                .emotes
+                .iter()
+                .filter(|emote| matches_search(emote, &search))
+                .collect();
+
+            if emotes.is_empty() {
+                return html! {};
+            }
+
+            html! {
+                <>
+                    <h5 class="mt-3 mb-2">{format!("{} ({})", group.provider_name, emotes.len())}</h5>
+
+                    <div class="gc-emotes-grid">
+                        {
+                            emotes.into_iter().map(|emote| {
+                                let title = if emote.channel {
+                                    format!("{} (channel)", emote.code)
+                                } else {
+                                    emote.code.clone()
+                                };
+
+                                html! {
+                                    <button
+                                        class="gc-emote-tile"
+                                        title={title}
+                                        onclick={get_copy_callback(emote.code.clone())}
+                                    >
+                                        <img src={emote.url.clone()} alt={emote.code.clone()} loading="lazy" />
+                                        <span class="gc-emote-code">{emote.code.clone()}</span>
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class={style}>
+            <input
+                type="text"
+                class="form-control"
+                placeholder="Search emotes"
+                value={(*search).clone()}
+                oninput={on_search_input} />
+
+            <div class="mt-2 form-text text-light">
+                {
+                    match &*copied_code {
+                        Some(code) => format!("Copied {}", code),
+                        None => "Click an emote to copy its code".to_string(),
+                    }
+                }
+            </div>
+
+            if emotes_groups.is_empty() {
+                <div class="mt-3">{"No emotes loaded, check the channel name and emote providers in settings"}</div>
+            }
+
+            {groups_html}
+        </div>
+    }
+}
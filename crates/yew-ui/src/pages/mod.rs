@@ -1,5 +1,7 @@
 mod chat;
+mod emotes;
 mod settings;
 
 pub use chat::ChatPage;
+pub use emotes::EmotesPage;
 pub use settings::SettingsPage;
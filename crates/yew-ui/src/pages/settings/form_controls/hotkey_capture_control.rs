@@ -0,0 +1,122 @@
+use yew::{
+    events::KeyboardEvent, function_component, html, AttrValue, Callback, Classes, Html, Properties,
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct HotkeyCaptureControlProps {
+    pub value: AttrValue,
+    pub on_change: Callback<String>,
+
+    #[prop_or_default]
+    pub class: Classes,
+
+    #[prop_or_default]
+    pub id: AttrValue,
+}
+
+// Maps `KeyboardEvent.code` to the key name used in Tauri accelerators
+fn get_accelerator_key(code: &str) -> Option<String> {
+    if let Some(letter) = code.strip_prefix("Key") {
+        return Some(letter.to_string());
+    }
+
+    if let Some(digit) = code.strip_prefix("Digit") {
+        return Some(digit.to_string());
+    }
+
+    if code.len() > 1 && code.starts_with('F') && code[1..].parse::<u8>().is_ok() {
+        return Some(code.to_string());
+    }
+
+    let key = match code {
+        "ArrowUp" => "Up",
+        "ArrowDown" => "Down",
+        "ArrowLeft" => "Left",
+        "ArrowRight" => "Right",
+        "Space" => "Space",
+        "Home" => "Home",
+        "End" => "End",
+        "PageUp" => "PageUp",
+        "PageDown" => "PageDown",
+        "Insert" => "Insert",
+        _ => return None,
+    };
+
+    Some(key.to_string())
+}
+
+fn is_macos() -> bool {
+    web_sys::window()
+        .and_then(|window| window.navigator().platform().ok())
+        .map_or(false, |platform| platform.starts_with("Mac"))
+}
+
+#[function_component(HotkeyCaptureControl)]
+pub fn hotkey_capture_control(props: &HotkeyCaptureControlProps) -> Html {
+    let on_key_down = {
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |e: KeyboardEvent| {
+            e.prevent_default();
+
+            let code = e.code();
+            let has_modifier = e.ctrl_key() || e.meta_key() || e.alt_key();
+
+            if !has_modifier && (code == "Backspace" || code == "Delete" || code == "Escape") {
+                on_change.emit(String::new());
+                return;
+            }
+
+            let key = match get_accelerator_key(&code) {
+                Some(key) => key,
+                None => return,
+            };
+
+            // Global shortcuts without modifiers would swallow regular typing
+            let is_function_key = key.len() > 1 && key.starts_with('F');
+            if !has_modifier && !is_function_key {
+                return;
+            }
+
+            let mut accelerator: Vec<String> = Vec::new();
+
+            // `CmdOrCtrl` is Cmd on macOS and Ctrl elsewhere, the other one is kept as is
+            let (ctrl, meta) = if is_macos() {
+                ("Ctrl", "CmdOrCtrl")
+            } else {
+                ("CmdOrCtrl", "Super")
+            };
+
+            if e.ctrl_key() {
+                accelerator.push(ctrl.to_string());
+            }
+
+            if e.meta_key() {
+                accelerator.push(meta.to_string());
+            }
+
+            if e.alt_key() {
+                accelerator.push("Alt".to_string());
+            }
+
+            if e.shift_key() {
+                accelerator.push("Shift".to_string());
+            }
+
+            accelerator.push(key);
+
+            on_change.emit(accelerator.join("+"));
+        })
+    };
+
+    html! {
+        <input
+            type="text"
+            readonly=true
+            id={props.id.clone()}
+            class={props.class.clone()}
+            placeholder="Press a shortcut, Backspace to disable"
+            value={props.value.clone()}
+            onkeydown={on_key_down} />
+    }
+}
@@ -1,5 +1,9 @@
+mod hotkey_capture_control;
 mod settings_input_control;
 mod settings_text_input_control;
+mod sound_rule_control;
 
+pub use hotkey_capture_control::HotkeyCaptureControl;
 pub use settings_input_control::SettingsInputControl;
 pub use settings_text_input_control::SettingsTextInputControl;
+pub use sound_rule_control::SoundRuleControl;
@@ -37,6 +37,29 @@ pub struct SettingsInputControlProps {
     pub step: Option<AttrValue>,
 }
 
+fn add_input_attributes(input_field: &mut VTag, props: &SettingsInputControlProps) {
+    input_field.add_attribute("type", props.input_type.clone());
+    input_field.add_attribute("class", classes!(props.class.clone()).to_string());
+    input_field.add_attribute("id", props.id.clone());
+
+    // Any value of `disabled` attribute disables the input, so it's only added when true
+    if props.disabled == Some(true) {
+        input_field.add_attribute("disabled", "true");
+    }
+
+    if let Some(min) = props.min.clone() {
+        input_field.add_attribute("min", min);
+    }
+
+    if let Some(max) = props.max.clone() {
+        input_field.add_attribute("max", max);
+    }
+
+    if let Some(step) = props.step.clone() {
+        input_field.add_attribute("step", step);
+    }
+}
+
 #[styled_component(SettingsInputControl)]
 pub fn settings_input_control(props: &SettingsInputControlProps) -> Html {
     let on_change = {
@@ -71,30 +94,10 @@ pub fn settings_input_control(props: &SettingsInputControlProps) -> Html {
         })
     };
 
-    let class_names = classes!(props.class.clone());
-
     let mut input_field = VTag::new("input");
-    input_field.add_attribute("type", props.input_type.clone());
-    input_field.add_attribute("class", class_names.to_string());
-    input_field.add_attribute("id", props.id.clone());
+    add_input_attributes(&mut input_field, props);
     input_field.set_value(props.value.clone());
 
-    if let Some(disabled) = props.disabled {
-        input_field.add_attribute("disabled", disabled.to_string());
-    }
-
-    if let Some(min) = props.min.clone() {
-        input_field.add_attribute("min", min);
-    }
-
-    if let Some(max) = props.max.clone() {
-        input_field.add_attribute("max", max);
-    }
-
-    if let Some(step) = props.step.clone() {
-        input_field.add_attribute("step", step);
-    }
-
     if props.on_change.clone().is_some() {
         input_field.add_listener(Rc::new(OnChangeWrapper::new(on_change)));
     }
@@ -115,3 +118,38 @@ pub fn settings_input_control(props: &SettingsInputControlProps) -> Html {
     //         oninput={on_input} />
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_disabled_attribute(disabled: Option<bool>) -> bool {
+        let props = SettingsInputControlProps {
+            on_change: None,
+            on_input: None,
+            value: "1".into(),
+            input_type: "number".into(),
+            class: Classes::new(),
+            id: "field".into(),
+            disabled,
+            min: None,
+            max: None,
+            step: None,
+        };
+
+        let mut input_field = VTag::new("input");
+        add_input_attributes(&mut input_field, &props);
+
+        input_field
+            .attributes
+            .iter()
+            .any(|(name, _)| name == "disabled")
+    }
+
+    #[test]
+    fn test_disabled_attribute() {
+        assert!(has_disabled_attribute(Some(true)));
+        assert!(!has_disabled_attribute(Some(false)));
+        assert!(!has_disabled_attribute(None));
+    }
+}
@@ -0,0 +1,138 @@
+use gigachat_models::SoundRule;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::{classes, function_component, html, AttrValue, Callback, Event, Html, Properties};
+
+use super::SettingsInputControl;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SoundRuleControlProps {
+    pub id: AttrValue,
+    pub label: AttrValue,
+    pub rule: SoundRule,
+    pub sounds: Vec<String>,
+    pub on_change: Callback<SoundRule>,
+    pub on_test: Callback<SoundRule>,
+}
+
+// Enable checkbox, sound, volume and test button of a single sound rule
+#[function_component(SoundRuleControl)]
+pub fn sound_rule_control(props: &SoundRuleControlProps) -> Html {
+    let on_enabled_change = {
+        let rule = props.rule.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            on_change.emit(SoundRule {
+                enabled: target.unchecked_into::<HtmlInputElement>().checked(),
+                ..rule.clone()
+            });
+        })
+    };
+
+    let on_sound_change = {
+        let rule = props.rule.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            on_change.emit(SoundRule {
+                sound: target.unchecked_into::<HtmlSelectElement>().value(),
+                ..rule.clone()
+            });
+        })
+    };
+
+    let on_volume_change = {
+        let rule = props.rule.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |value: String| {
+            on_change.emit(SoundRule {
+                volume: value.parse::<f32>().expect("Failed to parse volume value"),
+                ..rule.clone()
+            });
+        })
+    };
+
+    let on_test_click = {
+        let rule = props.rule.clone();
+        let on_test = props.on_test.clone();
+
+        Callback::from(move |_| {
+            on_test.emit(rule.clone());
+        })
+    };
+
+    let enabled_id = format!("{}_enabled", props.id);
+    let sound_id = format!("{}_sound", props.id);
+    let volume_id = format!("{}_volume", props.id);
+
+    // Keep the configured sound selectable even if its file was removed
+    let is_missing = !props.sounds.contains(&props.rule.sound);
+
+    html! {
+        <div class="mb-3 row align-items-center">
+            <div class="col-4">
+                <div class="form-check">
+                    <input
+                        type="checkbox"
+                        class="form-check-input"
+                        id={enabled_id.clone()}
+                        checked={props.rule.enabled}
+                        onchange={on_enabled_change} />
+
+                    <label class={classes!("form-check-label")} for={enabled_id}>{props.label.clone()}</label>
+                </div>
+            </div>
+
+            <div class="col-3">
+                <select
+                    class="form-select"
+                    id={sound_id}
+                    disabled={!props.rule.enabled}
+                    onchange={on_sound_change}
+                >
+                    {
+                        if is_missing {
+                            html! {
+                                <option value={props.rule.sound.clone()} selected=true>{format!("{} (missing)", props.rule.sound)}</option>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        props.sounds.iter().map(|sound| html! {
+                            <option value={sound.clone()} selected={*sound == props.rule.sound}>{sound}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </div>
+
+            <div class="col-3">
+                <SettingsInputControl
+                    input_type="range"
+                    id={volume_id}
+                    class={classes!("form-range")}
+                    disabled={!props.rule.enabled}
+                    on_change={on_volume_change}
+                    value={props.rule.volume.to_string()}
+                    step="0.05"
+                    min="0"
+                    max="1" />
+            </div>
+
+            <div class="col-2">
+                <button class="btn btn-secondary w-100" onclick={on_test_click}>{"Test"}</button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,7 @@
+use gigachat_models::HotkeySettings;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ValidateHotkeysArgs {
+    pub hotkeys: HotkeySettings,
+}
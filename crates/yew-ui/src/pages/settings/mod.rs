@@ -1,6 +1,9 @@
 mod form_controls;
+mod hotkeys_args;
+mod profile_args;
 mod save_settings_args;
 mod settings_form;
+mod sounds_args;
 
 use bounce::use_atom;
 use gigachat_models::AppSettings;
@@ -12,8 +15,13 @@ use tauri_sys::tauri::invoke;
 use wasm_bindgen_futures::spawn_local;
 use yew::{html, Callback, Html};
 
-pub use form_controls::{SettingsInputControl, SettingsTextInputControl};
+pub use form_controls::{
+    HotkeyCaptureControl, SettingsInputControl, SettingsTextInputControl, SoundRuleControl,
+};
+pub use hotkeys_args::ValidateHotkeysArgs;
+pub use profile_args::{SaveProfileArgs, SwitchProfileArgs};
 pub use save_settings_args::SaveSettingsArgs;
+pub use sounds_args::PlayTestSoundArgs;
 
 #[styled_component(SettingsPage)]
 pub fn settings_page() -> Html {
@@ -35,6 +43,45 @@ pub fn settings_page() -> Html {
                 font_size: settings.font_size,
                 enable_animation: settings.enable_animation,
                 messages_sort_asc: settings.messages_sort_asc,
+                theme: settings.theme,
+                font_family: settings.font_family,
+                font_weight: settings.font_weight,
+                text_shadow_style: settings.text_shadow_style,
+                text_shadow_color: settings.text_shadow_color,
+                text_shadow_width: settings.text_shadow_width,
+                line_height: settings.line_height,
+                message_spacing: settings.message_spacing,
+                use_user_name_colors: settings.use_user_name_colors,
+                generate_missing_name_colors: settings.generate_missing_name_colors,
+                adjust_name_colors: settings.adjust_name_colors,
+                active_profile: settings.active_profile,
+                hotkeys: settings.hotkeys,
+                interact_modifier: settings.interact_modifier,
+                interact_hotspot: settings.interact_hotspot,
+                max_messages_per_second: settings.max_messages_per_second,
+                rate_limit_strategy: settings.rate_limit_strategy,
+                privileged_only: settings.privileged_only,
+                min_message_length: settings.min_message_length,
+                collapse_emote_spam: settings.collapse_emote_spam,
+                collapse_duplicates: settings.collapse_duplicates,
+                duplicate_window_secs: settings.duplicate_window_secs,
+                tts_enabled: settings.tts_enabled,
+                tts_policy: settings.tts_policy,
+                tts_voice: settings.tts_voice,
+                tts_rate: settings.tts_rate,
+                tts_vary_voices: settings.tts_vary_voices,
+                sounds: settings.sounds,
+                overlay_server_enabled: settings.overlay_server_enabled,
+                overlay_server_port: settings.overlay_server_port,
+                control_api_enabled: settings.control_api_enabled,
+                control_api_token: settings.control_api_token,
+                overlay_windows: settings.overlay_windows,
+                disabled_emote_providers: settings.disabled_emote_providers,
+                strip_invisible_characters: settings.strip_invisible_characters,
+                emote_animation: settings.emote_animation,
+                emote_scale: settings.emote_scale,
+                show_stats_bar: settings.show_stats_bar,
+                live_emote_updates: settings.live_emote_updates,
                 ..prev_settings
             };
 
@@ -50,6 +97,7 @@ pub fn settings_page() -> Html {
 
     let style = css! {
         height: 100%;
+        overflow-y: auto;
         padding: 20px 15px 20px;
         background: #3c3c3c;
         color: #fafafa;
@@ -57,7 +105,10 @@ pub fn settings_page() -> Html {
 
     html! {
         <div data-tauri-drag-region="true" class={style}>
-            <SettingsForm initial_settings={(*app_settings).clone()} on_save={save_settings} />
+            <SettingsForm
+                key={app_settings.active_profile.clone()}
+                initial_settings={(*app_settings).clone()}
+                on_save={save_settings} />
         </div>
     }
 }
@@ -0,0 +1,15 @@
+use gigachat_models::AppSettings;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct SaveProfileArgs {
+    #[serde(rename = "profileName")]
+    pub profile_name: String,
+    pub settings: AppSettings,
+}
+
+#[derive(Serialize)]
+pub struct SwitchProfileArgs {
+    #[serde(rename = "profileName")]
+    pub profile_name: String,
+}
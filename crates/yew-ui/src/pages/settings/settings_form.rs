@@ -1,13 +1,23 @@
-use gigachat_models::AppSettings;
-use log::info;
+use chrono::{DateTime, Local};
+use gigachat_models::{
+    AppSettings, EmoteAnimation, EmoteFetchHealth, EmoteProviderInfo, EmoteProviderStatus,
+    EmoteScale, HotkeyConflict, HotkeySettings, InteractModifier, RateLimitStrategy, SoundRule,
+    SoundSettings, SoundsInfo, TextShadowStyle, ThemesInfo, TtsPolicy,
+};
+use log::{error, info};
 use stylist::yew::styled_component;
 use tauri_sys::tauri::invoke;
 use wasm_bindgen::JsCast;
 use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
 use yew::{platform::spawn_local, prelude::*};
+use yew_hooks::use_effect_once;
 
-use super::SaveSettingsArgs;
-use crate::pages::settings::{SettingsInputControl, SettingsTextInputControl};
+use super::{
+    PlayTestSoundArgs, SaveProfileArgs, SaveSettingsArgs, SwitchProfileArgs, ValidateHotkeysArgs,
+};
+use crate::pages::settings::{
+    HotkeyCaptureControl, SettingsInputControl, SettingsTextInputControl, SoundRuleControl,
+};
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct SettingsFormProps {
@@ -28,10 +38,79 @@ async fn sync_settings(settings: AppSettings) {
     });
 }
 
+fn get_fetch_health_html(scope_name: &str, health: &EmoteFetchHealth) -> Html {
+    let last_fetch = match health.last_fetch {
+        Some(last_fetch) => {
+            let last_fetch: DateTime<Local> = DateTime::from(last_fetch);
+            last_fetch.format("%H:%M:%S").to_string()
+        }
+        None => "never".to_string(),
+    };
+    let emote_count = health
+        .emote_count
+        .map_or("-".to_string(), |count| count.to_string());
+    let latency = health
+        .latency_ms
+        .map_or("-".to_string(), |latency| format!("{} ms", latency));
+
+    html! {
+        <div>
+            {format!(
+                "{}: last fetch {}, emotes: {}, latency: {}",
+                scope_name, last_fetch, emote_count, latency
+            )}
+            {
+                if let Some(last_error) = &health.last_error {
+                    html! { <div class="text-danger">{format!("Error: {}", last_error)}</div> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
 #[styled_component(SettingsForm)]
 pub fn settings_form(props: &SettingsFormProps) -> Html {
     let settings = use_state(|| props.initial_settings.clone());
     let channel_name = use_state(|| settings.channel_name.clone());
+    let themes_info = use_state(ThemesInfo::default);
+    let system_fonts = use_state(Vec::<String>::new);
+    let profiles = use_state(Vec::<String>::new);
+    let new_profile_name = use_state(String::new);
+    let hotkey_conflicts = use_state(Vec::<HotkeyConflict>::new);
+    let sounds_info = use_state(SoundsInfo::default);
+    let emote_providers = use_state(Vec::<EmoteProviderInfo>::new);
+
+    {
+        let themes_info = themes_info.clone();
+        let sounds_info = sounds_info.clone();
+        let emote_providers = emote_providers.clone();
+        let system_fonts = system_fonts.clone();
+        let profiles = profiles.clone();
+
+        use_effect_once(move || {
+            spawn_local(async move {
+                let info: ThemesInfo = invoke("get_themes", &()).await.unwrap();
+                themes_info.set(info);
+
+                let fonts: Vec<String> = invoke("get_system_fonts", &()).await.unwrap();
+                system_fonts.set(fonts);
+
+                let profiles_list: Vec<String> = invoke("get_profiles", &()).await.unwrap();
+                profiles.set(profiles_list);
+
+                let sounds: SoundsInfo = invoke("get_sounds", &()).await.unwrap();
+                sounds_info.set(sounds);
+
+                let providers: Vec<EmoteProviderInfo> =
+                    invoke("get_emote_providers", &()).await.unwrap();
+                emote_providers.set(providers);
+            });
+
+            || {}
+        });
+    }
 
     use_effect_with_deps(
         move |settings| {
@@ -44,6 +123,26 @@ pub fn settings_form(props: &SettingsFormProps) -> Html {
         settings.clone(),
     );
 
+    {
+        let hotkey_conflicts = hotkey_conflicts.clone();
+
+        use_effect_with_deps(
+            move |hotkeys| {
+                let hotkeys = hotkeys.clone();
+
+                spawn_local(async move {
+                    let conflicts: Vec<HotkeyConflict> =
+                        invoke("validate_hotkeys", &ValidateHotkeysArgs { hotkeys })
+                            .await
+                            .unwrap();
+
+                    hotkey_conflicts.set(conflicts);
+                });
+            },
+            settings.hotkeys.clone(),
+        );
+    }
+
     let on_save = props.on_save.clone();
     let save = {
         let settings = settings.clone();
@@ -121,6 +220,127 @@ pub fn settings_form(props: &SettingsFormProps) -> Html {
         })
     };
 
+    let on_theme_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+
+            settings.set(AppSettings {
+                theme: value,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_font_family_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+
+            settings.set(AppSettings {
+                font_family: value,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_font_weight_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+
+            settings.set(AppSettings {
+                font_weight: value
+                    .parse::<i32>()
+                    .expect("Failed to parse font weight value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_text_shadow_style_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let text_shadow_style = match value.as_str() {
+                "none" => TextShadowStyle::None,
+                "outline" => TextShadowStyle::Outline,
+                _ => TextShadowStyle::Shadow,
+            };
+
+            settings.set(AppSettings {
+                text_shadow_style,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_text_shadow_color_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                text_shadow_color: value,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_text_shadow_width_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                text_shadow_width: value
+                    .parse::<f32>()
+                    .expect("Failed to parse text outline width value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_line_height_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                line_height: value
+                    .parse::<f32>()
+                    .expect("Failed to parse line height value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_message_spacing_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                message_spacing: value
+                    .parse::<i32>()
+                    .expect("Failed to parse message spacing value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
     let on_enable_animation_change = {
         let settings = settings.clone();
 
@@ -138,112 +358,1613 @@ pub fn settings_form(props: &SettingsFormProps) -> Html {
         })
     };
 
-    let slider_setting_value_style = css! {
-        flex-grow: 0;
-        flex-shrink: 0;
-        flex-basis: 50px;
+    let on_use_user_name_colors_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                use_user_name_colors: checked,
+                ..(*settings).clone()
+            });
+        })
     };
 
-    let label_style = css! {
-        font-size: 16px;
-        font-weight: 200;
-        line-height: 38px;
+    let on_generate_missing_name_colors_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                generate_missing_name_colors: checked,
+                ..(*settings).clone()
+            });
+        })
     };
 
-    html! {
-        <div>
-            <div class="mb-3 row">
-                <label class={classes!("col-4", label_style.clone())} for="channel_name" >{"Channel Name"}</label>
+    let on_adjust_name_colors_change = {
+        let settings = settings.clone();
 
-                <div class="col-8">
-                    <SettingsTextInputControl
-                        id="channel_name"
-                        class={classes!("form-control")}
-                        on_change={on_channel_name_change.clone()}
-                        on_input={on_channel_name_change.clone()}
-                        value={(*channel_name).clone()}
-                    />
-                </div>
-            </div>
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
 
-            <div class="mb-3 row">
-                <label class={classes!("col-4", label_style.clone())} for="new_messages_sort">{"Sorting"}</label>
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
 
-                <div class="col-8">
-                    <select
-                        class="form-select"
-                        id="new_messages_sort"
-                        onchange={on_sort_change}
-                    >
-                        <option value="top" selected={!settings.messages_sort_asc}>{"New at the top"}</option>
-                        <option value="bottom" selected={settings.messages_sort_asc}>{"New at the bottom"}</option>
-                    </select>
-                </div>
-            </div>
+            settings.set(AppSettings {
+                adjust_name_colors: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
 
-            <div class="mb-3 row">
-                <label class={classes!("col-4", label_style.clone())} for="background_color">{"Background Color"}</label>
-                <div class="col-2">
-                    <SettingsInputControl
-                        input_type="color"
-                        id="background_color"
-                        class={classes!("form-control", "form-control-color", "p-0", "border-0")}
-                        on_input={on_background_color_change}
-                        value={settings.background_color.clone()} />
-                </div>
-            </div>
+    let on_profile_change = Callback::from(move |e: Event| {
+        let target: EventTarget = e
+            .target()
+            .expect("Event should have a target when dispatched");
+        let profile_name = target.unchecked_into::<HtmlSelectElement>().value();
 
-            <div class="mb-3 row">
-                <label class={classes!("col-4", label_style.clone())} for="background_opacity" class="form-label">{"Background Opacity"}</label>
+        if profile_name.is_empty() {
+            return;
+        }
 
-                <div class="col-8 d-flex align-items-center">
-                    <div class={classes!(slider_setting_value_style.clone())}>
-                        {(settings.background_opacity * 100_f32).floor().to_string()} {"%"}
-                    </div>
+        spawn_local(async move {
+            let result: Result<(), _> =
+                invoke("switch_profile", &SwitchProfileArgs { profile_name }).await;
 
-                    <SettingsInputControl
-                        input_type="range"
-                        id="background_opacity"
-                        class={classes!("form-range")}
-                        on_input={on_background_opacity_change}
-                        value={settings.background_opacity.to_string()}
-                        step="0.01"
-                        min="0"
-                        max="1" />
-                </div>
-            </div>
+            if let Err(err) = result {
+                error!("Failed to switch profile: {:?}", err);
+            }
+        });
+    });
 
-            <div class="mb-3 row">
-                <label class={classes!("col-4", label_style.clone())} for="font_size">{"Font Size"}</label>
+    let on_new_profile_name_change = {
+        let new_profile_name = new_profile_name.clone();
 
-                <div class="col-8 d-flex align-items-center">
-                    <div class={classes!(slider_setting_value_style)}>
-                        {settings.font_size.to_string()}
-                    </div>
+        Callback::from(move |value: String| {
+            new_profile_name.set(value);
+        })
+    };
 
-                    <SettingsInputControl
-                        input_type="range"
-                        id="font_size"
-                        class={classes!("form-range")}
-                        on_input={on_font_size_change}
-                        value={settings.font_size.to_string()}
-                        step="1"
-                        min="8"
-                        max="25" />
-                </div>
-            </div>
+    let save_profile = {
+        let settings = settings.clone();
+        let channel_name = channel_name.clone();
+        let profiles = profiles.clone();
+        let new_profile_name = new_profile_name.clone();
 
-            <div class="mb-3 form-check">
-                <input
-                    class="form-check-input"
-                    type="checkbox"
-                    class="form-check-input"
-                    id="enable_animation"
-                    checked={settings.enable_animation}
-                    onchange={on_enable_animation_change} />
+        Callback::from(move |_| {
+            let profile_name = new_profile_name.trim().to_string();
 
-                <label class={classes!("form-check-label")} for="enable_animation">{"Enable Animation"}</label>
-            </div>
+            if profile_name.is_empty() {
+                return;
+            }
+
+            let profile_settings = AppSettings {
+                channel_name: (*channel_name).clone(),
+                active_profile: profile_name.clone(),
+                ..(*settings).clone()
+            };
+
+            settings.set(profile_settings.clone());
+            new_profile_name.set(String::new());
+
+            let profiles = profiles.clone();
+
+            spawn_local(async move {
+                let result: Result<(), _> = invoke(
+                    "save_profile",
+                    &SaveProfileArgs {
+                        profile_name,
+                        settings: profile_settings,
+                    },
+                )
+                .await;
+
+                if let Err(err) = result {
+                    error!("Failed to save profile: {:?}", err);
+                }
+
+                let profiles_list: Vec<String> = invoke("get_profiles", &()).await.unwrap();
+                profiles.set(profiles_list);
+            });
+        })
+    };
+
+    let get_emote_provider_callback = |provider_id: String| {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            let mut disabled_emote_providers: Vec<String> = settings
+                .disabled_emote_providers
+                .iter()
+                .filter(|disabled_provider| **disabled_provider != provider_id)
+                .cloned()
+                .collect();
+
+            if !checked {
+                disabled_emote_providers.push(provider_id.clone());
+            }
+
+            settings.set(AppSettings {
+                disabled_emote_providers,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let get_overlay_window_callback = |profile_name: String| {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            let mut overlay_windows: Vec<String> = settings
+                .overlay_windows
+                .iter()
+                .filter(|overlay_profile| **overlay_profile != profile_name)
+                .cloned()
+                .collect();
+
+            if checked {
+                overlay_windows.push(profile_name.clone());
+            }
+
+            settings.set(AppSettings {
+                overlay_windows,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let get_hotkey_callback = |update_hotkeys: fn(&mut HotkeySettings, String)| {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            let mut hotkeys = settings.hotkeys.clone();
+            update_hotkeys(&mut hotkeys, value);
+
+            settings.set(AppSettings {
+                hotkeys,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let get_sound_rule_callback = |update_sounds: fn(&mut SoundSettings, SoundRule)| {
+        let settings = settings.clone();
+
+        Callback::from(move |rule: SoundRule| {
+            let mut sounds = settings.sounds.clone();
+            update_sounds(&mut sounds, rule);
+
+            settings.set(AppSettings {
+                sounds,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let sound_rule_controls = [
+        (
+            "sound_mention",
+            "Mentions",
+            settings.sounds.mention.clone(),
+            get_sound_rule_callback(|sounds, rule| sounds.mention = rule),
+        ),
+        (
+            "sound_first_message",
+            "First-Time Chatters",
+            settings.sounds.first_message.clone(),
+            get_sound_rule_callback(|sounds, rule| sounds.first_message = rule),
+        ),
+        (
+            "sound_subscription",
+            "Subscriptions",
+            settings.sounds.subscription.clone(),
+            get_sound_rule_callback(|sounds, rule| sounds.subscription = rule),
+        ),
+        (
+            "sound_raid",
+            "Raids",
+            settings.sounds.raid.clone(),
+            get_sound_rule_callback(|sounds, rule| sounds.raid = rule),
+        ),
+        (
+            "sound_keyword",
+            "Keywords",
+            settings.sounds.keyword.clone(),
+            get_sound_rule_callback(|sounds, rule| sounds.keyword = rule),
+        ),
+    ];
+
+    let on_test_sound = Callback::from(move |rule: SoundRule| {
+        spawn_local(async move {
+            let result: Result<(), _> = invoke(
+                "play_test_sound",
+                &PlayTestSoundArgs {
+                    sound: rule.sound,
+                    volume: rule.volume,
+                },
+            )
+            .await;
+
+            if let Err(err) = result {
+                error!("Failed to play test sound: {:?}", err);
+            }
+        });
+    });
+
+    let on_sound_keywords_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                sounds: SoundSettings {
+                    keywords: value,
+                    ..settings.sounds.clone()
+                },
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_sound_cooldown_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                sounds: SoundSettings {
+                    cooldown_secs: value
+                        .parse::<u32>()
+                        .expect("Failed to parse sound cooldown value"),
+                    ..settings.sounds.clone()
+                },
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let hotkey_controls = [
+        (
+            "hotkey_toggle_lock",
+            "Lock / Unlock",
+            settings.hotkeys.toggle_lock.clone(),
+            get_hotkey_callback(|hotkeys, value| hotkeys.toggle_lock = value),
+        ),
+        (
+            "hotkey_toggle_visibility",
+            "Show / Hide",
+            settings.hotkeys.toggle_visibility.clone(),
+            get_hotkey_callback(|hotkeys, value| hotkeys.toggle_visibility = value),
+        ),
+        (
+            "hotkey_toggle_always_on_top",
+            "Always On Top",
+            settings.hotkeys.toggle_always_on_top.clone(),
+            get_hotkey_callback(|hotkeys, value| hotkeys.toggle_always_on_top = value),
+        ),
+        (
+            "hotkey_clear_chat",
+            "Clear Chat",
+            settings.hotkeys.clear_chat.clone(),
+            get_hotkey_callback(|hotkeys, value| hotkeys.clear_chat = value),
+        ),
+        (
+            "hotkey_next_profile",
+            "Next Profile",
+            settings.hotkeys.next_profile.clone(),
+            get_hotkey_callback(|hotkeys, value| hotkeys.next_profile = value),
+        ),
+    ];
+
+    let on_interact_modifier_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let interact_modifier = match value.as_str() {
+                "ctrl" => InteractModifier::Ctrl,
+                "alt" => InteractModifier::Alt,
+                "shift" => InteractModifier::Shift,
+                _ => InteractModifier::None,
+            };
+
+            settings.set(AppSettings {
+                interact_modifier,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_interact_hotspot_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                interact_hotspot: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_max_messages_per_second_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                max_messages_per_second: value
+                    .parse::<u32>()
+                    .expect("Failed to parse max messages per second value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_rate_limit_strategy_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let rate_limit_strategy = match value.as_str() {
+                "sample" => RateLimitStrategy::Sample,
+                _ => RateLimitStrategy::Drop,
+            };
+
+            settings.set(AppSettings {
+                rate_limit_strategy,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_emote_animation_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let emote_animation = match value.as_str() {
+                "static" => EmoteAnimation::Static,
+                "on_hover" => EmoteAnimation::OnHover,
+                _ => EmoteAnimation::Animated,
+            };
+
+            settings.set(AppSettings {
+                emote_animation,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_emote_scale_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let emote_scale = match value.as_str() {
+                "x1" => EmoteScale::X1,
+                "x2" => EmoteScale::X2,
+                "x4" => EmoteScale::X4,
+                _ => EmoteScale::Auto,
+            };
+
+            settings.set(AppSettings {
+                emote_scale,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_show_stats_bar_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                show_stats_bar: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_live_emote_updates_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                live_emote_updates: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_min_message_length_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                min_message_length: value
+                    .parse::<u32>()
+                    .expect("Failed to parse min message length value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_privileged_only_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                privileged_only: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_collapse_emote_spam_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                collapse_emote_spam: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_strip_invisible_characters_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                strip_invisible_characters: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_duplicate_window_secs_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                duplicate_window_secs: value
+                    .parse::<u32>()
+                    .expect("Failed to parse duplicate window value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_collapse_duplicates_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                collapse_duplicates: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_tts_policy_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            let tts_policy = match value.as_str() {
+                "all" => TtsPolicy::All,
+                "highlights" => TtsPolicy::Highlights,
+                "subs_and_bits" => TtsPolicy::SubsAndBits,
+                _ => TtsPolicy::Mentions,
+            };
+
+            settings.set(AppSettings {
+                tts_policy,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_tts_voice_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                tts_voice: value,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_tts_rate_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            settings.set(AppSettings {
+                tts_rate: value
+                    .parse::<u32>()
+                    .expect("Failed to parse tts rate value"),
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_tts_enabled_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                tts_enabled: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_tts_vary_voices_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                tts_vary_voices: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_overlay_server_enabled_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+
+            settings.set(AppSettings {
+                overlay_server_enabled: checked,
+                ..(*settings).clone()
+            });
+        })
+    };
+
+    let on_overlay_server_port_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |value: String| {
+            // Keep previous port while the field is being edited
+            if let Ok(port) = value.parse::<u16>() {
+                settings.set(AppSettings {
+                    overlay_server_port: port,
+                    ..(*settings).clone()
+                });
+            }
+        })
+    };
+
+    let on_control_api_enabled_change = {
+        let settings = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+
+            let checked = target.unchecked_into::<HtmlInputElement>().checked();
+            let settings = settings.clone();
+
+            spawn_local(async move {
+                // API never works without a token, so one is created on first enable
+                let control_api_token = if checked && settings.control_api_token.is_empty() {
+                    invoke("generate_control_api_token", &()).await.unwrap()
+                } else {
+                    settings.control_api_token.clone()
+                };
+
+                settings.set(AppSettings {
+                    control_api_enabled: checked,
+                    control_api_token,
+                    ..(*settings).clone()
+                });
+            });
+        })
+    };
+
+    let open_emotes = Callback::from(|_| {
+        spawn_local(async move {
+            let _: () = invoke("open_emotes", &()).await.unwrap();
+        });
+    });
+
+    let refreshing_emotes = use_state(|| false);
+
+    let refresh_emotes = {
+        let emote_providers = emote_providers.clone();
+        let refreshing_emotes = refreshing_emotes.clone();
+
+        Callback::from(move |_| {
+            let emote_providers = emote_providers.clone();
+            let refreshing_emotes = refreshing_emotes.clone();
+            refreshing_emotes.set(true);
+
+            spawn_local(async move {
+                let _: () = invoke("refresh_emotes", &()).await.unwrap();
+
+                let providers: Vec<EmoteProviderInfo> =
+                    invoke("get_emote_providers", &()).await.unwrap();
+                emote_providers.set(providers);
+                refreshing_emotes.set(false);
+            });
+        })
+    };
+
+    let regenerate_control_api_token = {
+        let settings = settings.clone();
+
+        Callback::from(move |_| {
+            let settings = settings.clone();
+
+            spawn_local(async move {
+                let control_api_token: String =
+                    invoke("generate_control_api_token", &()).await.unwrap();
+
+                settings.set(AppSettings {
+                    control_api_token,
+                    ..(*settings).clone()
+                });
+            });
+        })
+    };
+
+    let slider_setting_value_style = css! {
+        flex-grow: 0;
+        flex-shrink: 0;
+        flex-basis: 50px;
+    };
+
+    let label_style = css! {
+        font-size: 16px;
+        font-weight: 200;
+        line-height: 38px;
+    };
+
+    html! {
+        <div>
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="channel_name" >{"Channel Name"}</label>
+
+                <div class="col-8">
+                    <SettingsTextInputControl
+                        id="channel_name"
+                        class={classes!("form-control")}
+                        on_change={on_channel_name_change.clone()}
+                        on_input={on_channel_name_change.clone()}
+                        value={(*channel_name).clone()}
+                    />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="new_messages_sort">{"Sorting"}</label>
+
+                <div class="col-8">
+                    <select
+                        class="form-select"
+                        id="new_messages_sort"
+                        onchange={on_sort_change}
+                    >
+                        <option value="top" selected={!settings.messages_sort_asc}>{"New at the top"}</option>
+                        <option value="bottom" selected={settings.messages_sort_asc}>{"New at the bottom"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="theme">{"Theme"}</label>
+
+                <div class="col-8">
+                    <select
+                        class="form-select"
+                        id="theme"
+                        onchange={on_theme_change}
+                    >
+                        {
+                            themes_info.themes.iter().map(|theme| html! {
+                                <option value={theme.clone()} selected={*theme == settings.theme}>{theme}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+
+                    <div class="form-text text-light">
+                        {"Custom styles are loaded from "}<code>{themes_info.user_theme_path.clone()}</code>
+                    </div>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="background_color">{"Background Color"}</label>
+                <div class="col-2">
+                    <SettingsInputControl
+                        input_type="color"
+                        id="background_color"
+                        class={classes!("form-control", "form-control-color", "p-0", "border-0")}
+                        on_input={on_background_color_change}
+                        value={settings.background_color.clone()} />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="background_opacity" class="form-label">{"Background Opacity"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {(settings.background_opacity * 100_f32).floor().to_string()} {"%"}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="background_opacity"
+                        class={classes!("form-range")}
+                        on_input={on_background_opacity_change}
+                        value={settings.background_opacity.to_string()}
+                        step="0.01"
+                        min="0"
+                        max="1" />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="font_size">{"Font Size"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {settings.font_size.to_string()}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="font_size"
+                        class={classes!("form-range")}
+                        on_input={on_font_size_change}
+                        value={settings.font_size.to_string()}
+                        step="1"
+                        min="8"
+                        max="25" />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="font_family">{"Font"}</label>
+
+                <div class="col-5">
+                    <select
+                        class="form-select"
+                        id="font_family"
+                        onchange={on_font_family_change}
+                    >
+                        <option value="" selected={settings.font_family.is_empty()}>{"Default"}</option>
+                        {
+                            system_fonts.iter().map(|font| html! {
+                                <option value={font.clone()} selected={*font == settings.font_family}>{font}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+
+                <div class="col-3">
+                    <select
+                        class="form-select"
+                        id="font_weight"
+                        onchange={on_font_weight_change}
+                    >
+                        {
+                            [(300, "Light"), (400, "Regular"), (500, "Medium"), (600, "Semibold"), (700, "Bold"), (900, "Black")]
+                                .iter()
+                                .map(|(weight, name)| html! {
+                                    <option value={weight.to_string()} selected={*weight == settings.font_weight}>{name}</option>
+                                })
+                                .collect::<Html>()
+                        }
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="text_shadow_style">{"Text Outline"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="text_shadow_style"
+                        onchange={on_text_shadow_style_change}
+                    >
+                        <option value="none" selected={settings.text_shadow_style == TextShadowStyle::None}>{"None"}</option>
+                        <option value="shadow" selected={settings.text_shadow_style == TextShadowStyle::Shadow}>{"Shadow"}</option>
+                        <option value="outline" selected={settings.text_shadow_style == TextShadowStyle::Outline}>{"Outline"}</option>
+                    </select>
+                </div>
+
+                <div class="col-2">
+                    <SettingsInputControl
+                        input_type="color"
+                        id="text_shadow_color"
+                        class={classes!("form-control", "form-control-color", "p-0", "border-0")}
+                        on_input={on_text_shadow_color_change}
+                        value={settings.text_shadow_color.clone()} />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="text_shadow_width">{"Outline Thickness"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {settings.text_shadow_width.to_string()}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="text_shadow_width"
+                        class={classes!("form-range")}
+                        on_input={on_text_shadow_width_change}
+                        value={settings.text_shadow_width.to_string()}
+                        step="0.5"
+                        min="0"
+                        max="4" />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="line_height">{"Line Height"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {format!("{:.1}", settings.line_height)}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="line_height"
+                        class={classes!("form-range")}
+                        on_input={on_line_height_change}
+                        value={settings.line_height.to_string()}
+                        step="0.1"
+                        min="1"
+                        max="2.5" />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="message_spacing">{"Message Spacing"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {settings.message_spacing.to_string()}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="message_spacing"
+                        class={classes!("form-range")}
+                        on_input={on_message_spacing_change}
+                        value={settings.message_spacing.to_string()}
+                        step="1"
+                        min="0"
+                        max="20" />
+                </div>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    class="form-check-input"
+                    type="checkbox"
+                    class="form-check-input"
+                    id="enable_animation"
+                    checked={settings.enable_animation}
+                    onchange={on_enable_animation_change} />
+
+                <label class={classes!("form-check-label")} for="enable_animation">{"Enable Animation"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="use_user_name_colors"
+                    checked={settings.use_user_name_colors}
+                    onchange={on_use_user_name_colors_change} />
+
+                <label class={classes!("form-check-label")} for="use_user_name_colors">{"Use Chatter Name Colors"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="generate_missing_name_colors"
+                    checked={settings.generate_missing_name_colors}
+                    onchange={on_generate_missing_name_colors_change} />
+
+                <label class={classes!("form-check-label")} for="generate_missing_name_colors">{"Generate Colors for Chatters Without One"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="adjust_name_colors"
+                    checked={settings.adjust_name_colors}
+                    onchange={on_adjust_name_colors_change} />
+
+                <label class={classes!("form-check-label")} for="adjust_name_colors">{"Improve Name Color Readability"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="show_stats_bar"
+                    checked={settings.show_stats_bar}
+                    onchange={on_show_stats_bar_change} />
+
+                <label class={classes!("form-check-label")} for="show_stats_bar">{"Show Chat Stats Bar (Top Emotes, Messages per Minute)"}</label>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Busy Chat"}</h5>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="max_messages_per_second">{"Max Messages / Second"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {
+                            if settings.max_messages_per_second == 0 {
+                                "Off".to_string()
+                            } else {
+                                settings.max_messages_per_second.to_string()
+                            }
+                        }
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="max_messages_per_second"
+                        class={classes!("form-range")}
+                        on_input={on_max_messages_per_second_change}
+                        value={settings.max_messages_per_second.to_string()}
+                        step="1"
+                        min="0"
+                        max="50" />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="rate_limit_strategy">{"Over the Limit"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="rate_limit_strategy"
+                        disabled={settings.max_messages_per_second == 0}
+                        onchange={on_rate_limit_strategy_change}
+                    >
+                        <option value="drop" selected={settings.rate_limit_strategy == RateLimitStrategy::Drop}>{"Drop"}</option>
+                        <option value="sample" selected={settings.rate_limit_strategy == RateLimitStrategy::Sample}>{"Sample"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="min_message_length">{"Min Message Length"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {
+                            if settings.min_message_length == 0 {
+                                "Off".to_string()
+                            } else {
+                                settings.min_message_length.to_string()
+                            }
+                        }
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="min_message_length"
+                        class={classes!("form-range")}
+                        on_input={on_min_message_length_change}
+                        value={settings.min_message_length.to_string()}
+                        step="1"
+                        min="0"
+                        max="50" />
+                </div>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="privileged_only"
+                    checked={settings.privileged_only}
+                    onchange={on_privileged_only_change} />
+
+                <label class={classes!("form-check-label")} for="privileged_only">{"Only Subscribers, VIPs and Moderators"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="collapse_emote_spam"
+                    checked={settings.collapse_emote_spam}
+                    onchange={on_collapse_emote_spam_change} />
+
+                <label class={classes!("form-check-label")} for="collapse_emote_spam">{"Collapse Repeated Emotes (e.g. KEKW ×37)"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="strip_invisible_characters"
+                    checked={settings.strip_invisible_characters}
+                    onchange={on_strip_invisible_characters_change} />
+
+                <label class={classes!("form-check-label")} for="strip_invisible_characters">{"Strip Invisible Characters (e.g. used to bypass duplicate message checks)"}</label>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="collapse_duplicates"
+                    checked={settings.collapse_duplicates}
+                    onchange={on_collapse_duplicates_change} />
+
+                <label class={classes!("form-check-label")} for="collapse_duplicates">{"Collapse Duplicate Messages"}</label>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="duplicate_window_secs">{"Duplicate Window (s)"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {settings.duplicate_window_secs.to_string()}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="duplicate_window_secs"
+                        class={classes!("form-range")}
+                        disabled={!settings.collapse_duplicates}
+                        on_input={on_duplicate_window_secs_change}
+                        value={settings.duplicate_window_secs.to_string()}
+                        step="1"
+                        min="1"
+                        max="60" />
+                </div>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Text to Speech"}</h5>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="tts_enabled"
+                    checked={settings.tts_enabled}
+                    onchange={on_tts_enabled_change} />
+
+                <label class={classes!("form-check-label")} for="tts_enabled">{"Read Messages Aloud (requires espeak-ng)"}</label>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="tts_policy">{"Messages to Read"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="tts_policy"
+                        disabled={!settings.tts_enabled}
+                        onchange={on_tts_policy_change}
+                    >
+                        <option value="all" selected={settings.tts_policy == TtsPolicy::All}>{"All"}</option>
+                        <option value="highlights" selected={settings.tts_policy == TtsPolicy::Highlights}>{"Highlighted"}</option>
+                        <option value="mentions" selected={settings.tts_policy == TtsPolicy::Mentions}>{"Mentions"}</option>
+                        <option value="subs_and_bits" selected={settings.tts_policy == TtsPolicy::SubsAndBits}>{"Subscribers & Cheers"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="tts_voice">{"Voice"}</label>
+
+                <div class="col-4">
+                    <SettingsInputControl
+                        id="tts_voice"
+                        class={classes!("form-control")}
+                        disabled={!settings.tts_enabled}
+                        on_change={on_tts_voice_change}
+                        value={settings.tts_voice.clone()} />
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="tts_rate">{"Speech Rate"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style.clone())}>
+                        {settings.tts_rate.to_string()}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="tts_rate"
+                        class={classes!("form-range")}
+                        disabled={!settings.tts_enabled}
+                        on_input={on_tts_rate_change}
+                        value={settings.tts_rate.to_string()}
+                        step="5"
+                        min="80"
+                        max="400" />
+                </div>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="tts_vary_voices"
+                    checked={settings.tts_vary_voices}
+                    onchange={on_tts_vary_voices_change} />
+
+                <label class={classes!("form-check-label")} for="tts_vary_voices">{"Different Voice for Every Chatter"}</label>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Sounds"}</h5>
+
+            {
+                sound_rule_controls.into_iter().map(|(id, label, rule, on_change)| html! {
+                    <SoundRuleControl
+                        id={id}
+                        label={label}
+                        rule={rule}
+                        sounds={sounds_info.sounds.clone()}
+                        on_change={on_change}
+                        on_test={on_test_sound.clone()} />
+                }).collect::<Html>()
+            }
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="sound_keywords">{"Keywords"}</label>
+
+                <div class="col-8">
+                    <SettingsTextInputControl
+                        id="sound_keywords"
+                        class={classes!("form-control")}
+                        on_change={on_sound_keywords_change}
+                        value={settings.sounds.keywords.clone()} />
+
+                    <div class="form-text text-light">
+                        {"Comma separated. Custom sounds are loaded from "}<code>{sounds_info.user_sounds_path.clone()}</code>
+                    </div>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="sound_cooldown">{"Sound Cooldown"}</label>
+
+                <div class="col-8 d-flex align-items-center">
+                    <div class={classes!(slider_setting_value_style)}>
+                        {format!("{}s", settings.sounds.cooldown_secs)}
+                    </div>
+
+                    <SettingsInputControl
+                        input_type="range"
+                        id="sound_cooldown"
+                        class={classes!("form-range")}
+                        on_input={on_sound_cooldown_change}
+                        value={settings.sounds.cooldown_secs.to_string()}
+                        step="1"
+                        min="0"
+                        max="60" />
+                </div>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Browser Source"}</h5>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="overlay_server_enabled"
+                    checked={settings.overlay_server_enabled}
+                    onchange={on_overlay_server_enabled_change} />
+
+                <label class={classes!("form-check-label")} for="overlay_server_enabled">{"Serve Chat for OBS Browser Source"}</label>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="overlay_server_port">{"Port"}</label>
+
+                <div class="col-4">
+                    <SettingsInputControl
+                        input_type="number"
+                        id="overlay_server_port"
+                        class={classes!("form-control")}
+                        disabled={!settings.overlay_server_enabled}
+                        on_change={on_overlay_server_port_change}
+                        value={settings.overlay_server_port.to_string()}
+                        min="1024"
+                        max="65535" />
+                </div>
+
+                <div class="col-8 offset-4 form-text text-light">
+                    {"Add browser source with URL "}<code>{format!("http://127.0.0.1:{}/", settings.overlay_server_port)}</code>
+                </div>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Control API"}</h5>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="control_api_enabled"
+                    checked={settings.control_api_enabled}
+                    onchange={on_control_api_enabled_change} />
+
+                <label class={classes!("form-check-label")} for="control_api_enabled">{"Enable Local Control API"}</label>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="control_api_token">{"Token"}</label>
+
+                <div class="col-5">
+                    <input
+                        type="text"
+                        readonly=true
+                        class="form-control"
+                        id="control_api_token"
+                        disabled={!settings.control_api_enabled}
+                        value={settings.control_api_token.clone()} />
+                </div>
+
+                <div class="col-3">
+                    <button
+                        class="btn btn-secondary w-100"
+                        disabled={!settings.control_api_enabled}
+                        onclick={regenerate_control_api_token}
+                    >
+                        {"Regenerate"}
+                    </button>
+                </div>
+
+                <div class="col-8 offset-4 form-text text-light">
+                    {"Send commands to "}<code>{format!("http://127.0.0.1:{}/api/actions", settings.overlay_server_port)}</code>
+                    {" with "}<code>{"Authorization: Bearer <token>"}</code>
+                </div>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Emote Providers"}</h5>
+
+            {
+                emote_providers.iter().map(|provider| {
+                    let id = format!("emote_provider_{}", provider.id);
+                    let status = match provider.health.get_status() {
+                        EmoteProviderStatus::Unknown => "Not loaded",
+                        EmoteProviderStatus::Ok => "OK",
+                        EmoteProviderStatus::Failed => "Failed",
+                    };
+
+                    html! {
+                        <div class="mb-3 form-check">
+                            <input
+                                type="checkbox"
+                                class="form-check-input"
+                                id={id.clone()}
+                                checked={!settings.disabled_emote_providers.contains(&provider.id)}
+                                onchange={get_emote_provider_callback(provider.id.clone())} />
+
+                            <label class={classes!("form-check-label")} for={id}>{provider.name.clone()}</label>
+
+                            <div class="form-text text-light">{status}</div>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+
+            <div class="mb-3 form-text text-light">
+                {"Emotes with the same code are taken from the provider listed first"}
+            </div>
+
+            <div class="mb-3">
+                <button class="btn btn-secondary" onclick={open_emotes}>{"Browse Emotes"}</button>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Emote Diagnostics"}</h5>
+
+            {
+                emote_providers.iter().map(|provider| {
+                    html! {
+                        <div class="mb-2 row">
+                            <div class={classes!("col-4", label_style.clone())}>{provider.name.clone()}</div>
+
+                            <div class="col-8 form-text text-light">
+                                {get_fetch_health_html("Global", &provider.health.global)}
+                                {get_fetch_health_html("Channel", &provider.health.channel)}
+                            </div>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+
+            <div class="mb-3 form-text text-light">
+                {"Channel results are of the last fetched channel"}
+            </div>
+
+            <div class="mb-3">
+                <button
+                    class="btn btn-secondary"
+                    disabled={*refreshing_emotes}
+                    onclick={refresh_emotes}
+                >
+                    {if *refreshing_emotes { "Refreshing..." } else { "Refresh Emotes" }}
+                </button>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="emote_animation">{"Animated Emotes"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="emote_animation"
+                        onchange={on_emote_animation_change}
+                    >
+                        <option value="animated" selected={settings.emote_animation == EmoteAnimation::Animated}>{"Animate"}</option>
+                        <option value="static" selected={settings.emote_animation == EmoteAnimation::Static}>{"Don't Animate"}</option>
+                        <option value="on_hover" selected={settings.emote_animation == EmoteAnimation::OnHover}>{"Animate On Hover"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="emote_scale">{"Emote Image Size"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="emote_scale"
+                        onchange={on_emote_scale_change}
+                    >
+                        <option value="auto" selected={settings.emote_scale == EmoteScale::Auto}>{"Auto"}</option>
+                        <option value="x1" selected={settings.emote_scale == EmoteScale::X1}>{"1x"}</option>
+                        <option value="x2" selected={settings.emote_scale == EmoteScale::X2}>{"2x"}</option>
+                        <option value="x4" selected={settings.emote_scale == EmoteScale::X4}>{"4x"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="live_emote_updates"
+                    checked={settings.live_emote_updates}
+                    onchange={on_live_emote_updates_change} />
+
+                <label class={classes!("form-check-label")} for="live_emote_updates">{"Live Emote Updates (announce emotes added to 7tv and BetterTTV mid-stream)"}</label>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Locked Overlay Interaction"}</h5>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="interact_modifier">{"Hold To Interact"}</label>
+
+                <div class="col-4">
+                    <select
+                        class="form-select"
+                        id="interact_modifier"
+                        onchange={on_interact_modifier_change}
+                    >
+                        <option value="none" selected={settings.interact_modifier == InteractModifier::None}>{"Disabled"}</option>
+                        <option value="ctrl" selected={settings.interact_modifier == InteractModifier::Ctrl}>{"Ctrl"}</option>
+                        <option value="alt" selected={settings.interact_modifier == InteractModifier::Alt}>{"Alt"}</option>
+                        <option value="shift" selected={settings.interact_modifier == InteractModifier::Shift}>{"Shift"}</option>
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 form-check">
+                <input
+                    type="checkbox"
+                    class="form-check-input"
+                    id="interact_hotspot"
+                    checked={settings.interact_hotspot}
+                    onchange={on_interact_hotspot_change} />
+
+                <label class={classes!("form-check-label")} for="interact_hotspot">{"Hover top-right corner to interact"}</label>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Profiles"}</h5>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="active_profile">{"Active Profile"}</label>
+
+                <div class="col-8">
+                    <select
+                        class="form-select"
+                        id="active_profile"
+                        onchange={on_profile_change}
+                    >
+                        <option value="" selected={settings.active_profile.is_empty()} disabled=true>{"No profile"}</option>
+                        {
+                            profiles.iter().map(|profile| html! {
+                                <option value={profile.clone()} selected={*profile == settings.active_profile}>{profile}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+            </div>
+
+            <div class="mb-3 row">
+                <label class={classes!("col-4", label_style.clone())} for="new_profile_name">{"Save as Profile"}</label>
+
+                <div class="col-5">
+                    <SettingsTextInputControl
+                        id="new_profile_name"
+                        class={classes!("form-control")}
+                        on_input={on_new_profile_name_change}
+                        value={(*new_profile_name).clone()}
+                    />
+                </div>
+
+                <div class="col-3">
+                    <button class="btn btn-secondary w-100" onclick={save_profile}>{"Save Profile"}</button>
+                </div>
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Overlay Windows"}</h5>
+
+            {
+                profiles.iter().map(|profile| {
+                    let id = format!("overlay_window_{}", profile);
+
+                    html! {
+                        <div class="mb-3 form-check">
+                            <input
+                                type="checkbox"
+                                class="form-check-input"
+                                id={id.clone()}
+                                checked={settings.overlay_windows.contains(profile)}
+                                onchange={get_overlay_window_callback(profile.clone())} />
+
+                            <label class={classes!("form-check-label")} for={id}>{format!("Show \"{}\" in separate window", profile)}</label>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+
+            <div class="mb-3 form-text text-light">
+                {"Every window shows chat with channel, filters and style of its profile. Save a profile to update its window"}
+            </div>
+
+            <h5 class="mt-4 mb-3">{"Hotkeys"}</h5>
+
+            {
+                hotkey_controls.into_iter().map(|(id, label, value, on_change)| html! {
+                    <div class="mb-3 row">
+                        <label class={classes!("col-4", label_style.clone())} for={id}>{label}</label>
+
+                        <div class="col-8">
+                            <HotkeyCaptureControl
+                                id={id}
+                                class={classes!("form-control")}
+                                value={value}
+                                on_change={on_change} />
+                        </div>
+                    </div>
+                }).collect::<Html>()
+            }
+
+            {
+                hotkey_conflicts.iter().map(|conflict| html! {
+                    <div class="alert alert-warning py-1">
+                        {format!("{} ({}): {}", conflict.action, conflict.accelerator, conflict.reason)}
+                    </div>
+                }).collect::<Html>()
+            }
 
             <div class="d-flex justify-content-end">
                 <button class="btn btn-primary" onclick={save}>{"Save"}</button>
@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct PlayTestSoundArgs {
+    pub sound: String,
+    pub volume: f32,
+}
@@ -6,4 +6,6 @@ pub enum Route {
     Chat,
     #[at("/settings")]
     Settings,
+    #[at("/emotes")]
+    Emotes,
 }
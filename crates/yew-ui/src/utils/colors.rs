@@ -1,3 +1,17 @@
+use gigachat_models::fnv1a_hash;
+
+pub type Rgb = (u32, u32, u32);
+
+// Color used for sender names when there is no color to show
+pub const FALLBACK_NAME_COLOR: &str = "#999999";
+
+// Minimal WCAG contrast ratio for names (AA for normal text)
+const MIN_NAME_CONTRAST_RATIO: f64 = 4.5;
+
+// Chat background is translucent and we can't know what is behind the window,
+// so it is assumed to be blended with a neutral mid gray
+const ASSUMED_BACKDROP: Rgb = (128, 128, 128);
+
 pub fn hex_to_rgb(hex: &str) -> (u32, u32, u32) {
     let mut c: Vec<char> = hex.chars().collect();
     if c.len() == 4 {
@@ -8,3 +22,290 @@ pub fn hex_to_rgb(hex: &str) -> (u32, u32, u32) {
 
     ((c >> 16) & 255, (c >> 8) & 255, c & 255)
 }
+
+// Parses `#rgb` or `#rrggbb` color, returns None for anything else
+pub fn parse_hex_color(color: &str) -> Option<Rgb> {
+    let hex = color.strip_prefix('#')?;
+
+    if !(hex.len() == 3 || hex.len() == 6) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    if hex.len() == 3 {
+        Some(hex_to_rgb(color))
+    } else {
+        Some(hex_to_rgb(hex))
+    }
+}
+
+pub fn rgb_to_hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2)
+}
+
+// WCAG 2.x relative luminance
+pub fn relative_luminance(rgb: Rgb) -> f64 {
+    let channel = |c: u32| {
+        let c = c as f64 / 255_f64;
+
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * channel(rgb.0) + 0.7152 * channel(rgb.1) + 0.0722 * channel(rgb.2)
+}
+
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (l1, l2) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+// Color the user actually sees behind the text for translucent background
+pub fn effective_background(background: Rgb, opacity: f32) -> Rgb {
+    let opacity = opacity.clamp(0_f32, 1_f32) as f64;
+    let blend =
+        |fg: u32, bg: u32| (fg as f64 * opacity + bg as f64 * (1_f64 - opacity)).round() as u32;
+
+    (
+        blend(background.0, ASSUMED_BACKDROP.0),
+        blend(background.1, ASSUMED_BACKDROP.1),
+        blend(background.2, ASSUMED_BACKDROP.2),
+    )
+}
+
+// Mixes color towards white (dark background) or black (light background)
+// until it reaches required contrast ratio
+pub fn readable_color(color: Rgb, background: Rgb, min_ratio: f64) -> Rgb {
+    if contrast_ratio(color, background) >= min_ratio {
+        return color;
+    }
+
+    let target: Rgb =
+        if contrast_ratio((255, 255, 255), background) >= contrast_ratio((0, 0, 0), background) {
+            (255, 255, 255)
+        } else {
+            (0, 0, 0)
+        };
+    let mix = |from: u32, to: u32, amount: f64| {
+        (from as f64 + (to as f64 - from as f64) * amount).round() as u32
+    };
+
+    (1..=20)
+        .map(|step| {
+            let amount = step as f64 / 20_f64;
+
+            (
+                mix(color.0, target.0, amount),
+                mix(color.1, target.1, amount),
+                mix(color.2, target.2, amount),
+            )
+        })
+        .find(|candidate| contrast_ratio(*candidate, background) >= min_ratio)
+        .unwrap_or(target)
+}
+
+// Stable color for a user based on their login
+pub fn deterministic_color(login: &str) -> Rgb {
+    let hash = fnv1a_hash(login);
+
+    hsl_to_rgb((hash % 360) as f64, 0.7, 0.6)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let c = (1_f64 - (2_f64 * lightness - 1_f64).abs()) * saturation;
+    let x = c * (1_f64 - ((hue / 60_f64) % 2_f64 - 1_f64).abs());
+    let m = lightness - c / 2_f64;
+
+    let (r, g, b) = match hue as u32 {
+        0..=59 => (c, x, 0_f64),
+        60..=119 => (x, c, 0_f64),
+        120..=179 => (0_f64, c, x),
+        180..=239 => (0_f64, x, c),
+        240..=299 => (x, 0_f64, c),
+        _ => (c, 0_f64, x),
+    };
+    let to_channel = |v: f64| ((v + m) * 255_f64).round() as u32;
+
+    (to_channel(r), to_channel(g), to_channel(b))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameColorOptions {
+    pub use_user_colors: bool,
+    pub generate_missing_colors: bool,
+    pub adjust_for_contrast: bool,
+    pub background_color: String,
+    pub background_opacity: f32,
+}
+
+// Resolves color of the sender name according to settings
+pub fn resolve_name_color(color: Option<&str>, login: &str, options: &NameColorOptions) -> String {
+    let user_color = color
+        .filter(|_| options.use_user_colors)
+        .and_then(parse_hex_color);
+
+    let color = match user_color {
+        Some(rgb) => rgb,
+        None if options.generate_missing_colors => deterministic_color(login),
+        None => return FALLBACK_NAME_COLOR.to_string(),
+    };
+
+    if !options.adjust_for_contrast {
+        return rgb_to_hex(color);
+    }
+
+    let background = effective_background(
+        parse_hex_color(&options.background_color).unwrap_or((0, 0, 0)),
+        options.background_opacity,
+    );
+
+    rgb_to_hex(readable_color(color, background, MIN_NAME_CONTRAST_RATIO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_options() -> NameColorOptions {
+        NameColorOptions {
+            use_user_colors: true,
+            generate_missing_colors: true,
+            adjust_for_contrast: true,
+            background_color: "#000000".to_string(),
+            background_opacity: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_hex_to_rgb() {
+        assert_eq!(hex_to_rgb("ff8000"), (255, 128, 0));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#FF8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex_color("#f80"), Some((255, 136, 0)));
+        assert_eq!(parse_hex_color("red"), None);
+        assert_eq!(parse_hex_color("#12345"), None);
+        assert_eq!(parse_hex_color("#gggggg"), None);
+    }
+
+    #[test]
+    fn test_rgb_to_hex() {
+        assert_eq!(rgb_to_hex((255, 128, 0)), "#ff8000");
+    }
+
+    #[test]
+    fn test_relative_luminance() {
+        assert!((relative_luminance((0, 0, 0)) - 0.0).abs() < 1e-9);
+        assert!((relative_luminance((255, 255, 255)) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio((255, 255, 255), (0, 0, 0)) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio((120, 30, 200), (120, 30, 200)) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_effective_background() {
+        assert_eq!(effective_background((0, 0, 0), 1.0), (0, 0, 0));
+        assert_eq!(effective_background((0, 0, 0), 0.0), ASSUMED_BACKDROP);
+        assert_eq!(effective_background((0, 0, 0), 0.5), (64, 64, 64));
+    }
+
+    #[test]
+    fn test_readable_color_keeps_readable_color() {
+        assert_eq!(readable_color((255, 255, 0), (0, 0, 0), 4.5), (255, 255, 0));
+    }
+
+    #[test]
+    fn test_readable_color_lightens_dark_color_on_dark_background() {
+        let result = readable_color((0, 0, 139), (0, 0, 0), 4.5);
+
+        assert_ne!(result, (0, 0, 139));
+        assert!(contrast_ratio(result, (0, 0, 0)) >= 4.5);
+    }
+
+    #[test]
+    fn test_readable_color_darkens_light_color_on_light_background() {
+        let result = readable_color((255, 255, 200), (255, 255, 255), 4.5);
+
+        assert!(contrast_ratio(result, (255, 255, 255)) >= 4.5);
+    }
+
+    #[test]
+    fn test_deterministic_color_is_stable() {
+        assert_eq!(
+            deterministic_color("some_user"),
+            deterministic_color("some_user")
+        );
+        assert_ne!(
+            deterministic_color("some_user"),
+            deterministic_color("other_user")
+        );
+    }
+
+    #[test]
+    fn test_resolve_name_color_adjusts_user_color() {
+        let result = resolve_name_color(Some("#00008B"), "user", &get_options());
+        let result_rgb = parse_hex_color(&result).unwrap();
+
+        assert!(contrast_ratio(result_rgb, (0, 0, 0)) >= 4.5);
+    }
+
+    #[test]
+    fn test_resolve_name_color_without_adjustment() {
+        let options = NameColorOptions {
+            adjust_for_contrast: false,
+            ..get_options()
+        };
+
+        assert_eq!(
+            resolve_name_color(Some("#00008B"), "user", &options),
+            "#00008b"
+        );
+    }
+
+    #[test]
+    fn test_resolve_name_color_missing_color() {
+        let options = NameColorOptions {
+            adjust_for_contrast: false,
+            ..get_options()
+        };
+
+        assert_eq!(
+            resolve_name_color(None, "user", &options),
+            rgb_to_hex(deterministic_color("user"))
+        );
+
+        let options = NameColorOptions {
+            generate_missing_colors: false,
+            ..options
+        };
+
+        assert_eq!(
+            resolve_name_color(None, "user", &options),
+            FALLBACK_NAME_COLOR
+        );
+    }
+
+    #[test]
+    fn test_resolve_name_color_user_colors_disabled() {
+        let options = NameColorOptions {
+            use_user_colors: false,
+            generate_missing_colors: false,
+            ..get_options()
+        };
+
+        assert_eq!(
+            resolve_name_color(Some("#ff0000"), "user", &options),
+            FALLBACK_NAME_COLOR
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::cell::{Cell, RefCell};
+
+use futures::{channel::mpsc, future, stream::LocalBoxStream, StreamExt};
+use gloo_net::websocket::{futures::WebSocket, Message};
+use gloo_timers::future::TimeoutFuture;
+use log::{error, warn};
+use serde::{de::DeserializeOwned, Deserialize};
+use wasm_bindgen_futures::spawn_local;
+
+const RECONNECT_DELAY_MS: u32 = 1000;
+
+// Event sent by the overlay server, same names and payloads as Tauri events
+#[derive(Deserialize)]
+struct ServerEvent {
+    event: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+type Subscriber = (String, mpsc::UnboundedSender<serde_json::Value>);
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<Subscriber>> = RefCell::new(Vec::new());
+    static CONNECTION_STARTED: Cell<bool> = Cell::new(false);
+}
+
+// Tauri injects IPC handle into app windows only. The same UI opened in a browser
+// (e.g. OBS browser source) receives events from the overlay server over WebSocket
+pub fn is_browser_source() -> bool {
+    web_sys::window().map_or(false, |window| window.get("__TAURI_IPC__").is_none())
+}
+
+// Overlay server route with a single query parameter, e.g. `/theme.css?theme=...`
+pub fn get_server_path(path: &str, key: &str, value: &str) -> String {
+    format!(
+        "{}?{}={}",
+        path,
+        key,
+        String::from(js_sys::encode_uri_component(value))
+    )
+}
+
+fn get_server_url() -> Option<String> {
+    let host = web_sys::window()?.location().host().ok()?;
+
+    Some(format!("ws://{}/ws", host))
+}
+
+fn dispatch(text: &str) {
+    let server_event = match serde_json::from_str::<ServerEvent>(text) {
+        Ok(server_event) => server_event,
+        Err(err) => {
+            error!("Failed to parse server event: {:?}", err);
+            return;
+        }
+    };
+
+    SUBSCRIBERS.with(|subscribers| {
+        subscribers.borrow_mut().retain(|(event, sender)| {
+            *event != server_event.event
+                || sender.unbounded_send(server_event.payload.clone()).is_ok()
+        })
+    });
+}
+
+// Single connection is shared by all listeners and is reopened when the app is restarted
+fn start_connection() {
+    if CONNECTION_STARTED.with(|started| started.replace(true)) {
+        return;
+    }
+
+    spawn_local(async {
+        loop {
+            match get_server_url().map(|url| WebSocket::open(&url)) {
+                Some(Ok(mut socket)) => {
+                    while let Some(message) = socket.next().await {
+                        match message {
+                            Ok(Message::Text(text)) => dispatch(&text),
+                            Ok(Message::Bytes(_)) => {}
+                            Err(err) => {
+                                warn!("Overlay server connection closed: {:?}", err);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(Err(err)) => error!("Failed to connect to overlay server: {:?}", err),
+                None => error!("Failed to get overlay server url"),
+            }
+
+            TimeoutFuture::new(RECONNECT_DELAY_MS).await;
+        }
+    });
+}
+
+// Stream of event payloads, from Tauri in app windows or from the overlay server in a browser
+pub async fn listen_event<T: DeserializeOwned + 'static>(
+    event: &str,
+) -> LocalBoxStream<'static, T> {
+    if !is_browser_source() {
+        return tauri_sys::event::listen::<T>(event)
+            .await
+            .unwrap()
+            .map(|tauri_event| tauri_event.payload)
+            .boxed_local();
+    }
+
+    let (sender, receiver) = mpsc::unbounded();
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().push((event.to_string(), sender)));
+
+    start_connection();
+
+    let event = event.to_string();
+    receiver
+        .filter_map(move |payload| {
+            let payload = serde_json::from_value::<T>(payload)
+                .map_err(|err| error!("Failed to parse '{}' payload: {:?}", event, err))
+                .ok();
+
+            future::ready(payload)
+        })
+        .boxed_local()
+}
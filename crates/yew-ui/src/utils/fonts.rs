@@ -0,0 +1,37 @@
+// Builds `font-family` css value for the font picked in settings.
+// Family names come from the system and profiles, so they are escaped
+// to keep quotes, `;` or `}` from breaking the generated stylesheet
+pub fn font_family_css(font_family: &str) -> String {
+    if font_family.is_empty() {
+        return "inherit".to_string();
+    }
+
+    let escaped: String = font_family
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+                c.to_string()
+            } else {
+                // Trailing space ends the hex escape
+                format!("\\{:x} ", c as u32)
+            }
+        })
+        .collect();
+
+    format!("\"{}\", sans-serif", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_family_css() {
+        assert_eq!(font_family_css(""), "inherit");
+        assert_eq!(font_family_css("Open Sans"), "\"Open Sans\", sans-serif");
+        assert_eq!(
+            font_family_css("Evil\"; } body {"),
+            "\"Evil\\22 \\3b  \\7d  body \\7b \", sans-serif"
+        );
+    }
+}
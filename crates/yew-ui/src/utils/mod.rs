@@ -1 +1,5 @@
 pub mod colors;
+pub mod events;
+pub mod fonts;
+pub mod text_shadow;
+pub mod virtual_list;
@@ -0,0 +1,29 @@
+use gigachat_models::TextShadowStyle;
+
+const OUTLINE_OFFSETS: [(f32, f32); 8] = [
+    (-1.0, -1.0),
+    (0.0, -1.0),
+    (1.0, -1.0),
+    (-1.0, 0.0),
+    (1.0, 0.0),
+    (-1.0, 1.0),
+    (0.0, 1.0),
+    (1.0, 1.0),
+];
+
+// Builds `text-shadow` css value for the message text
+pub fn text_shadow_css(style: TextShadowStyle, color: &str, width: f32) -> String {
+    if width <= 0_f32 {
+        return "none".to_string();
+    }
+
+    match style {
+        TextShadowStyle::None => "none".to_string(),
+        TextShadowStyle::Shadow => format!("{w}px {w}px 0 {c}", w = width, c = color),
+        TextShadowStyle::Outline => OUTLINE_OFFSETS
+            .iter()
+            .map(|(x, y)| format!("{}px {}px 0 {}", x * width, y * width, color))
+            .collect::<Vec<String>>()
+            .join(", "),
+    }
+}
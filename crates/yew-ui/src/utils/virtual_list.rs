@@ -0,0 +1,117 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibleRange {
+    pub start: usize,
+    pub end: usize,
+    // Height of the rows which are not rendered before and after the visible ones
+    pub offset_before: f64,
+    pub offset_after: f64,
+}
+
+// Finds rows intersecting the viewport, extended by `overscan` rows on each side.
+// Scroll position is clamped, so `f64::MAX` can be used to get the last rows
+pub fn get_visible_range(
+    heights: &[f64],
+    scroll_top: f64,
+    viewport_height: f64,
+    overscan: usize,
+) -> VisibleRange {
+    let total_height: f64 = heights.iter().sum();
+    let scroll_top = scroll_top.clamp(0_f64, (total_height - viewport_height).max(0_f64));
+    let scroll_bottom = scroll_top + viewport_height;
+
+    let mut first_visible = heights.len();
+    let mut last_visible = heights.len();
+    let mut row_top = 0_f64;
+
+    for (index, height) in heights.iter().enumerate() {
+        let row_bottom = row_top + height;
+
+        if first_visible == heights.len() && row_bottom > scroll_top {
+            first_visible = index;
+        }
+
+        if row_top >= scroll_bottom {
+            last_visible = index;
+            break;
+        }
+
+        row_top = row_bottom;
+    }
+
+    let start = first_visible.min(heights.len()).saturating_sub(overscan);
+    let end = (last_visible + overscan).min(heights.len()).max(start);
+
+    VisibleRange {
+        start,
+        end,
+        offset_before: heights[..start].iter().sum(),
+        offset_after: heights[end..].iter().sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_range_at_top() {
+        let heights = vec![10_f64; 100];
+
+        let range = get_visible_range(&heights, 0_f64, 50_f64, 2);
+
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 7);
+        assert_eq!(range.offset_before, 0_f64);
+        assert_eq!(range.offset_after, 930_f64);
+    }
+
+    #[test]
+    fn test_visible_range_in_the_middle() {
+        let heights = vec![10_f64; 100];
+
+        let range = get_visible_range(&heights, 505_f64, 50_f64, 2);
+
+        assert_eq!(range.start, 48);
+        assert_eq!(range.end, 58);
+        assert_eq!(range.offset_before, 480_f64);
+        assert_eq!(range.offset_after, 420_f64);
+    }
+
+    #[test]
+    fn test_visible_range_clamps_to_end() {
+        let heights = vec![10_f64; 100];
+
+        let range = get_visible_range(&heights, f64::MAX, 50_f64, 2);
+
+        assert_eq!(range.start, 93);
+        assert_eq!(range.end, 100);
+        assert_eq!(range.offset_after, 0_f64);
+    }
+
+    #[test]
+    fn test_visible_range_variable_heights() {
+        let heights = vec![10_f64, 40_f64, 10_f64, 10_f64, 40_f64, 10_f64];
+
+        let range = get_visible_range(&heights, 55_f64, 10_f64, 0);
+
+        assert_eq!((range.start, range.end), (2, 4));
+        assert_eq!(range.offset_before, 50_f64);
+        assert_eq!(range.offset_after, 50_f64);
+    }
+
+    #[test]
+    fn test_visible_range_fits_viewport() {
+        let heights = vec![10_f64; 3];
+
+        let range = get_visible_range(&heights, f64::MAX, 50_f64, 2);
+
+        assert_eq!((range.start, range.end), (0, 3));
+    }
+
+    #[test]
+    fn test_visible_range_empty() {
+        let range = get_visible_range(&[], 0_f64, 50_f64, 2);
+
+        assert_eq!((range.start, range.end), (0, 0));
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::Mutex;
+
+use gigachat_models::{AppSettings, UpdateAlwaysOnTopEventArgs, UpdateIgnoreEventsArgs};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    services::{
+        EmotesService, HotkeysService, LiveEmotesService, OverlayServerService,
+        OverlayWindowsService, ProfilesService, SettingsService, TtsService, MAIN_WINDOW_LABEL,
+    },
+    state::{
+        app_settings_state::{toggle_always_on_top_state, toggle_ui_lock_state},
+        ui_lock_state::UiLockState,
+    },
+    utils::{emit_clear_chat, emit_sync_settings, emit_toggle_always_on_top, emit_toggle_ui_lock},
+};
+
+pub fn set_ui_lock(app_handle: &AppHandle, is_ui_locked: bool) {
+    toggle_ui_lock_state(app_handle, is_ui_locked);
+
+    emit_toggle_ui_lock(
+        app_handle,
+        UpdateIgnoreEventsArgs {
+            ignore_events: is_ui_locked,
+        },
+    );
+}
+
+pub fn toggle_ui_lock(app_handle: &AppHandle) {
+    let ui_locked_state = app_handle.state::<UiLockState>();
+    let ui_locked_state = ui_locked_state.lock().unwrap();
+    let new_lock_value = !*ui_locked_state;
+    drop(ui_locked_state);
+
+    set_ui_lock(app_handle, new_lock_value);
+}
+
+pub fn set_always_on_top(app_handle: &AppHandle, always_on_top: bool) {
+    toggle_always_on_top_state(app_handle, always_on_top);
+
+    emit_toggle_always_on_top(app_handle, UpdateAlwaysOnTopEventArgs { always_on_top });
+}
+
+pub fn toggle_always_on_top(app_handle: &AppHandle) {
+    let app_settings_state = app_handle.state::<Mutex<AppSettings>>();
+    let app_settings_state = app_settings_state.lock().unwrap();
+    let new_always_on_top_state = !app_settings_state.always_on_top;
+    drop(app_settings_state);
+
+    set_always_on_top(app_handle, new_always_on_top_state);
+}
+
+// All overlay windows follow visibility of the main one
+pub fn toggle_overlay_visibility(app_handle: &AppHandle) {
+    let main_window = app_handle.get_window(MAIN_WINDOW_LABEL).unwrap();
+    let is_visible = main_window.is_visible().unwrap_or(true);
+
+    for window in OverlayWindowsService::get_overlay_windows(app_handle) {
+        let result = if is_visible {
+            window.hide()
+        } else {
+            window.show()
+        };
+
+        if let Err(err) = result {
+            error!(
+                "Failed to toggle visibility of '{}': {:?}",
+                window.label(),
+                err
+            );
+        }
+    }
+}
+
+pub fn clear_chat(app_handle: &AppHandle) {
+    emit_clear_chat(app_handle);
+}
+
+pub fn skip_tts_message(app_handle: &AppHandle) {
+    app_handle.state::<TtsService>().skip();
+}
+
+pub fn clear_tts_queue(app_handle: &AppHandle) {
+    app_handle.state::<TtsService>().clear();
+}
+
+// Applies new settings to the running app and persists them
+pub fn apply_settings(app_handle: &AppHandle, new_settings: AppSettings) {
+    let new_settings = SettingsService::normalize_settings(new_settings);
+
+    let old_settings = {
+        let settings_state = app_handle.state::<Mutex<AppSettings>>();
+        let mut settings = settings_state.lock().unwrap();
+        let old_settings = (*settings).clone();
+        *settings = new_settings.clone();
+
+        old_settings
+    };
+
+    if let Err(err) = SettingsService::save_settings(app_handle, &new_settings) {
+        error!("Failed to save settings: {:?}", err);
+    }
+
+    debug!("Applying settings current: {}", old_settings);
+    debug!("Applying settings new: {}", new_settings);
+
+    if old_settings.channel_name != new_settings.channel_name {
+        info!("changing channel to: {}", new_settings.channel_name);
+    }
+
+    // Also joins the new channel and leaves the old one, unless another overlay window shows it
+    OverlayWindowsService::apply_settings(app_handle, &new_settings);
+
+    if old_settings.disabled_emote_providers != new_settings.disabled_emote_providers {
+        EmotesService::reload_emotes(app_handle);
+    }
+
+    if old_settings.live_emote_updates != new_settings.live_emote_updates {
+        app_handle
+            .state::<LiveEmotesService>()
+            .update_subscriptions(app_handle);
+    }
+
+    if old_settings.hotkeys != new_settings.hotkeys {
+        HotkeysService::register_hotkeys(app_handle, &new_settings.hotkeys);
+    }
+
+    if let Err(err) = app_handle
+        .state::<OverlayServerService>()
+        .apply_settings(app_handle, &new_settings)
+    {
+        error!("{:?}", err);
+    }
+
+    toggle_always_on_top_state(app_handle, new_settings.always_on_top);
+
+    emit_sync_settings(app_handle, &new_settings);
+}
+
+pub fn switch_channel(app_handle: &AppHandle, channel_name: &str) {
+    let current_settings = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .clone();
+
+    apply_settings(
+        app_handle,
+        AppSettings {
+            channel_name: channel_name.to_string(),
+            ..current_settings
+        },
+    );
+}
+
+pub fn switch_profile(app_handle: &AppHandle, profile_name: &str) -> anyhow::Result<()> {
+    info!("Switching to profile: {}", profile_name);
+
+    let profile_settings = ProfilesService::load_profile(app_handle, profile_name)?;
+    let current_settings = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .clone();
+
+    apply_settings(
+        app_handle,
+        ProfilesService::merge_profile(profile_name, profile_settings, &current_settings),
+    );
+
+    Ok(())
+}
+
+pub fn switch_to_next_profile(app_handle: &AppHandle) {
+    let active_profile = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .active_profile
+        .clone();
+    let profiles = ProfilesService::get_profiles(app_handle);
+
+    if let Some(next_profile) = ProfilesService::get_next_profile(&profiles, &active_profile) {
+        if let Err(err) = switch_profile(app_handle, &next_profile) {
+            error!("Failed to switch profile: {:?}", err);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use clap::Parser;
+use gigachat_models::AppSettings;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    actions,
+    services::{OverlayWindowsService, ProfilesService, SettingsService},
+};
+
+#[derive(Parser, Debug, Clone, Default, PartialEq, Eq)]
+#[command(name = "gigachat", version, about = "Twitch chat overlay")]
+pub struct CliArgs {
+    #[arg(long, value_name = "CHANNEL", help = "Twitch channel to join")]
+    pub channel: Option<String>,
+
+    #[arg(long, value_name = "PROFILE", help = "Settings profile to activate")]
+    pub profile: Option<String>,
+
+    #[arg(long, conflicts_with = "unlocked", help = "Lock overlay")]
+    pub locked: bool,
+
+    #[arg(long, help = "Unlock overlay to move and resize it")]
+    pub unlocked: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory for settings, profiles, themes and sounds"
+    )]
+    pub config_dir: Option<PathBuf>,
+
+    #[arg(long, help = "Replace saved settings with defaults")]
+    pub reset_settings: bool,
+}
+
+impl CliArgs {
+    pub fn get_lock_state(&self) -> Option<bool> {
+        if self.locked {
+            Some(true)
+        } else if self.unlocked {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // Saved settings with profile and channel from arguments applied on top, channel wins
+    pub fn get_startup_settings(&self, app_handle: &AppHandle) -> AppSettings {
+        let settings = if self.reset_settings {
+            info!("Resetting settings to defaults");
+
+            let settings = AppSettings::default();
+            if let Err(err) = SettingsService::save_settings(app_handle, &settings) {
+                error!("Failed to reset settings: {:?}", err);
+            }
+
+            settings
+        } else {
+            SettingsService::get_settings(app_handle)
+        };
+
+        let settings = match &self.profile {
+            Some(profile_name) => match ProfilesService::load_profile(app_handle, profile_name) {
+                Ok(profile_settings) => {
+                    ProfilesService::merge_profile(profile_name, profile_settings, &settings)
+                }
+                Err(err) => {
+                    error!("Failed to load profile '{}': {:?}", profile_name, err);
+                    settings
+                }
+            },
+            None => settings,
+        };
+
+        match &self.channel {
+            Some(channel_name) => AppSettings {
+                channel_name: SettingsService::normalize_channel_name(channel_name),
+                ..settings
+            },
+            None => settings,
+        }
+    }
+
+    // Handles arguments forwarded by a second instance of the app
+    pub fn apply_to_running_app(&self, app_handle: &AppHandle) {
+        if self.config_dir.is_some() || self.reset_settings {
+            warn!("--config-dir and --reset-settings are ignored by the running instance");
+        }
+
+        if let Some(profile_name) = &self.profile {
+            if let Err(err) = actions::switch_profile(app_handle, profile_name) {
+                error!("Failed to switch profile: {:?}", err);
+            }
+        }
+
+        if let Some(channel_name) = &self.channel {
+            let current_channel = app_handle
+                .state::<Mutex<AppSettings>>()
+                .lock()
+                .unwrap()
+                .channel_name
+                .clone();
+            let channel_name = SettingsService::normalize_channel_name(channel_name);
+
+            if channel_name != current_channel {
+                actions::switch_channel(app_handle, &channel_name);
+            }
+        }
+
+        if let Some(is_ui_locked) = self.get_lock_state() {
+            actions::set_ui_lock(app_handle, is_ui_locked);
+        }
+
+        for window in OverlayWindowsService::get_overlay_windows(app_handle) {
+            let _ = window.show();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, clap::Error> {
+        CliArgs::try_parse_from(std::iter::once("gigachat").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_parse_no_args() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&[
+            "--channel",
+            "streamer",
+            "--profile",
+            "gaming",
+            "--unlocked",
+            "--config-dir",
+            "/tmp/gigachat",
+            "--reset-settings",
+        ])
+        .unwrap();
+
+        assert_eq!(args.channel, Some("streamer".to_string()));
+        assert_eq!(args.profile, Some("gaming".to_string()));
+        assert_eq!(args.get_lock_state(), Some(false));
+        assert_eq!(args.config_dir, Some(PathBuf::from("/tmp/gigachat")));
+        assert!(args.reset_settings);
+    }
+
+    #[test]
+    fn test_locked_conflicts_with_unlocked() {
+        assert!(parse(&["--locked", "--unlocked"]).is_err());
+        assert_eq!(parse(&["--locked"]).unwrap().get_lock_state(), Some(true));
+    }
+}
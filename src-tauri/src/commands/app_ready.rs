@@ -1,9 +1,8 @@
-use std::sync::Mutex;
-
-use gigachat_models::{AppSettings, UpdateIgnoreEventsArgs};
-use tauri::AppHandle;
+use gigachat_models::UpdateIgnoreEventsArgs;
+use tauri::{AppHandle, Window};
 
 use crate::{
+    services::OverlayWindowsService,
     state::ui_lock_state::UiLockState,
     utils::{emit_init_settings, emit_toggle_ui_lock},
 };
@@ -11,13 +10,13 @@ use crate::{
 #[tauri::command]
 pub fn app_ready(
     app_handle: AppHandle,
-    settings_state: tauri::State<'_, Mutex<AppSettings>>,
+    window: Window,
     ui_lock_state: tauri::State<'_, UiLockState>,
 ) {
-    info!("'app_ready' command invoked");
+    info!("'app_ready' command invoked by '{}'", window.label());
 
-    let settings = settings_state.lock().unwrap();
-    emit_init_settings(&app_handle, &settings);
+    let settings = OverlayWindowsService::get_window_settings(&app_handle, window.label());
+    emit_init_settings(&window, &settings);
 
     let is_ui_locked = ui_lock_state.lock().unwrap();
     emit_toggle_ui_lock(
@@ -0,0 +1,6 @@
+use crate::services::ControlApiService;
+
+#[tauri::command]
+pub fn generate_control_api_token() -> String {
+    ControlApiService::generate_token()
+}
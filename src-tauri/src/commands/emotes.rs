@@ -0,0 +1,58 @@
+use std::sync::Mutex;
+
+use gigachat_models::{AppSettings, EmoteProviderInfo, EmotesGroup};
+use tauri::{AppHandle, ClipboardManager};
+
+use crate::{
+    emote_providers::EMOTE_PROVIDERS, services::EmotesService,
+    state::saved_emotes_state::get_saved_emotes_state, utils::open_emotes_window,
+};
+
+#[tauri::command]
+pub fn get_emote_providers(
+    settings_state: tauri::State<'_, Mutex<AppSettings>>,
+) -> Vec<EmoteProviderInfo> {
+    let settings = settings_state.lock().unwrap();
+
+    EMOTE_PROVIDERS.get_providers_info(&settings.disabled_emote_providers)
+}
+
+// Emotes of the main overlay channel for the emote browser
+#[tauri::command]
+pub fn get_emotes(
+    app_handle: AppHandle,
+    settings_state: tauri::State<'_, Mutex<AppSettings>>,
+) -> Vec<EmotesGroup> {
+    let (channel_name, disabled_providers) = {
+        let settings = settings_state.lock().unwrap();
+        (
+            settings.channel_name.clone(),
+            settings.disabled_emote_providers.clone(),
+        )
+    };
+
+    let emotes = get_saved_emotes_state(&app_handle, &channel_name);
+
+    EmotesService::get_emotes_groups(
+        &emotes,
+        &EMOTE_PROVIDERS.get_providers_info(&disabled_providers),
+    )
+}
+
+// Resolves once all emotes are fetched, so that the caller can show fresh provider health
+#[tauri::command]
+pub async fn refresh_emotes(app_handle: AppHandle) {
+    EmotesService::refresh_emotes(&app_handle).await;
+}
+
+#[tauri::command]
+pub async fn open_emotes(app_handle: AppHandle) {
+    open_emotes_window(&app_handle);
+}
+
+#[tauri::command]
+pub fn copy_to_clipboard(app_handle: AppHandle, text: String) {
+    if let Err(err) = app_handle.clipboard_manager().write_text(text) {
+        error!("Failed to copy to clipboard: {:?}", err);
+    }
+}
@@ -0,0 +1,15 @@
+use font_kit::source::SystemSource;
+
+// Returns sorted list of font families installed in the system
+#[tauri::command]
+pub async fn get_system_fonts() -> Vec<String> {
+    let mut families = SystemSource::new().all_families().unwrap_or_else(|err| {
+        error!("Failed to enumerate system fonts: {:?}", err);
+        Vec::new()
+    });
+
+    families.sort_unstable_by_key(|family| family.to_lowercase());
+    families.dedup();
+
+    families
+}
@@ -0,0 +1,9 @@
+use gigachat_models::{HotkeyConflict, HotkeySettings};
+use tauri::AppHandle;
+
+use crate::services::HotkeysService;
+
+#[tauri::command]
+pub fn validate_hotkeys(app_handle: AppHandle, hotkeys: HotkeySettings) -> Vec<HotkeyConflict> {
+    HotkeysService::validate_hotkeys(&app_handle, &hotkeys)
+}
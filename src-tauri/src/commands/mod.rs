@@ -1,26 +1,32 @@
 use gigachat_models::AppSettings;
-use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 mod app_ready;
+mod control_api;
+mod emotes;
+mod fonts;
+mod hotkeys;
+mod profiles;
 mod save_settings;
+mod sounds;
+mod stats;
+mod themes;
 
 pub use app_ready::app_ready;
+pub use control_api::generate_control_api_token;
+pub use emotes::{copy_to_clipboard, get_emote_providers, get_emotes, open_emotes, refresh_emotes};
+pub use fonts::get_system_fonts;
+pub use hotkeys::validate_hotkeys;
+pub use profiles::{get_profiles, save_profile, switch_profile};
 pub use save_settings::save_settings;
+pub use sounds::{get_sounds, play_test_sound};
+pub use stats::get_chat_stats;
+pub use themes::{get_theme_css, get_themes};
 
-use crate::{state::app_settings_state::toggle_ui_lock_state, utils::open_settings_window};
-
-// Returns old settings
-fn update_settings_state(
-    settings_state: tauri::State<'_, Mutex<AppSettings>>,
-    new_settings: AppSettings,
-) -> AppSettings {
-    let mut settings = settings_state.lock().unwrap();
-    let old_settings = (*settings).clone();
-    *settings = new_settings;
-
-    old_settings
-}
+use crate::{
+    state::app_settings_state::toggle_ui_lock_state,
+    utils::{emit_sync_settings, open_settings_window},
+};
 
 #[tauri::command]
 pub fn unlock_ui(app_handle: AppHandle) {
@@ -39,7 +45,5 @@ pub async fn open_settings(app_handle: AppHandle) {
 
 #[tauri::command]
 pub fn sync_settings(app_handle: AppHandle, new_settings: AppSettings) {
-    app_handle
-        .emit_all("sync_settings", new_settings)
-        .expect("Failed to emit sync_settings event");
+    emit_sync_settings(&app_handle, &new_settings);
 }
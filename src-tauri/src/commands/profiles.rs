@@ -0,0 +1,46 @@
+use std::sync::Mutex;
+
+use gigachat_models::AppSettings;
+use tauri::AppHandle;
+
+use crate::{
+    actions,
+    services::{OverlayWindowsService, ProfilesService, SettingsService},
+};
+
+#[tauri::command]
+pub fn get_profiles(app_handle: AppHandle) -> Vec<String> {
+    ProfilesService::get_profiles(&app_handle)
+}
+
+#[tauri::command]
+pub fn save_profile(
+    app_handle: AppHandle,
+    settings_state: tauri::State<'_, Mutex<AppSettings>>,
+    profile_name: String,
+    settings: AppSettings,
+) -> Result<(), String> {
+    let settings = SettingsService::normalize_settings(AppSettings {
+        active_profile: profile_name.clone(),
+        ..settings
+    });
+
+    ProfilesService::save_profile(&app_handle, &profile_name, &settings)
+        .map_err(|err| err.to_string())?;
+
+    OverlayWindowsService::update_profile(&app_handle, &profile_name, &settings);
+
+    // Saved profile becomes the active one, also after a restart
+    let app_settings = {
+        let mut settings_state = settings_state.lock().unwrap();
+        settings_state.active_profile = profile_name;
+        settings_state.clone()
+    };
+
+    SettingsService::save_settings(&app_handle, &app_settings).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn switch_profile(app_handle: AppHandle, profile_name: String) -> Result<(), String> {
+    actions::switch_profile(&app_handle, &profile_name).map_err(|err| err.to_string())
+}
@@ -0,0 +1,26 @@
+use gigachat_models::SoundsInfo;
+use tauri::AppHandle;
+
+use crate::services::SoundsService;
+
+#[tauri::command]
+pub fn get_sounds(app_handle: AppHandle) -> SoundsInfo {
+    SoundsInfo {
+        sounds: SoundsService::get_sound_names(&app_handle),
+        user_sounds_path: SoundsService::get_user_sounds_dir(&app_handle)
+            .to_string_lossy()
+            .to_string(),
+    }
+}
+
+#[tauri::command]
+pub fn play_test_sound(
+    app_handle: AppHandle,
+    sounds_service: tauri::State<'_, SoundsService>,
+    sound: String,
+    volume: f32,
+) -> Result<(), String> {
+    sounds_service
+        .play(&app_handle, &sound, volume)
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,10 @@
+use gigachat_models::ChatStats;
+use tauri::AppHandle;
+
+use crate::services::StatsService;
+
+// Overlay windows may show different channels, so the window passes its own one
+#[tauri::command]
+pub fn get_chat_stats(app_handle: AppHandle, channel_name: String) -> ChatStats {
+    StatsService::get_channel_stats(&app_handle, &channel_name)
+}
@@ -0,0 +1,19 @@
+use gigachat_models::ThemesInfo;
+use tauri::AppHandle;
+
+use crate::services::ThemeService;
+
+#[tauri::command]
+pub fn get_themes(app_handle: AppHandle) -> ThemesInfo {
+    ThemesInfo {
+        themes: ThemeService::get_bundled_theme_names(),
+        user_theme_path: ThemeService::get_user_theme_path(&app_handle)
+            .to_string_lossy()
+            .to_string(),
+    }
+}
+
+#[tauri::command]
+pub fn get_theme_css(app_handle: AppHandle, theme_name: String) -> String {
+    ThemeService::get_theme_css(&app_handle, &theme_name)
+}
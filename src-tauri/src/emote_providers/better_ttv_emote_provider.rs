@@ -1,9 +1,10 @@
 use async_trait::async_trait;
+use gigachat_models::EmoteProviderCapabilities;
 use serde::{Deserialize, Serialize};
 
-use crate::state::saved_emotes_state::EmotesMap;
+use crate::state::saved_emotes_state::{EmoteUrls, EmotesMap};
 
-use super::EmoteProvider;
+use super::{EmoteProvider, EmoteUpdate, LiveEmoteProvider};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct BetterTtvEmote {
@@ -26,14 +27,76 @@ struct BetterTtvUserResponse {
     pub shared_emotes: Vec<BetterTtvEmote>,
 }
 
-fn map_betterttv_emotes(emotes: Vec<BetterTtvEmote>) -> EmotesMap {
-    emotes
-        .into_iter()
-        .map(|emote| {
-            let url = format!("https://cdn.betterttv.net/emote/{}/1x.webp", emote.id);
-            (emote.code, url)
+const BETTERTTV_SCALES: [u32; 3] = [1, 2, 3];
+
+fn map_betterttv_emote(emote: BetterTtvEmote) -> (String, EmoteUrls) {
+    let urls = BETTERTTV_SCALES
+        .iter()
+        .map(|scale| {
+            let url = format!(
+                "https://cdn.betterttv.net/emote/{}/{}x.webp",
+                emote.id, scale
+            );
+            (*scale, url)
         })
-        .collect()
+        .collect();
+    let static_urls = emote.animated.then(|| {
+        BETTERTTV_SCALES
+            .iter()
+            .map(|scale| {
+                let url = format!(
+                    "https://cdn.betterttv.net/emote/{}/static/{}x.webp",
+                    emote.id, scale
+                );
+                (*scale, url)
+            })
+            .collect()
+    });
+
+    (
+        emote.code,
+        EmoteUrls {
+            emote_id: emote.id,
+            ..EmoteUrls::new(urls, static_urls)
+        },
+    )
+}
+
+fn map_betterttv_emotes(emotes: Vec<BetterTtvEmote>) -> EmotesMap {
+    emotes.into_iter().map(map_betterttv_emote).collect()
+}
+
+// Renamed emote has no image details, only the new code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BetterTtvEmoteCode {
+    pub id: String,
+    pub code: String,
+}
+
+// Messages of the BetterTTV socket, channels are named like `twitch:<user id>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "name", content = "data", rename_all = "snake_case")]
+enum BetterTtvEvent {
+    JoinChannel {
+        name: String,
+    },
+    EmoteCreate {
+        channel: String,
+        emote: BetterTtvEmote,
+    },
+    EmoteUpdate {
+        channel: String,
+        emote: BetterTtvEmoteCode,
+    },
+    EmoteDelete {
+        channel: String,
+        #[serde(rename = "emoteId")]
+        emote_id: String,
+    },
+}
+
+fn get_betterttv_channel(twitch_user_id: &str) -> String {
+    format!("twitch:{}", twitch_user_id)
 }
 
 pub struct BetterTtvEmoteProvider {
@@ -56,10 +119,23 @@ impl Default for BetterTtvEmoteProvider {
 
 #[async_trait]
 impl EmoteProvider for BetterTtvEmoteProvider {
+    fn get_id(&self) -> &'static str {
+        "betterttv"
+    }
+
     fn get_name(&self) -> &'static str {
         "BetterTTV"
     }
 
+    fn get_capabilities(&self) -> EmoteProviderCapabilities {
+        EmoteProviderCapabilities {
+            global_emotes: true,
+            channel_emotes: true,
+            badges: false,
+            live_updates: true,
+        }
+    }
+
     async fn get_global_emotes(&self) -> anyhow::Result<EmotesMap> {
         let client = self.reqwest_client.clone();
 
@@ -92,4 +168,71 @@ impl EmoteProvider for BetterTtvEmoteProvider {
 
         Ok(emotes)
     }
+
+    fn get_live_updates(&self) -> Option<&dyn LiveEmoteProvider> {
+        Some(self)
+    }
+}
+
+// Socket doesn't tell who changed emotes
+#[async_trait]
+impl LiveEmoteProvider for BetterTtvEmoteProvider {
+    fn get_events_url(&self) -> &'static str {
+        "wss://sockets.betterttv.net/ws"
+    }
+
+    async fn get_subscribe_messages<'a>(
+        &self,
+        twitch_user_id: &'a str,
+    ) -> anyhow::Result<Vec<String>> {
+        let join_channel = BetterTtvEvent::JoinChannel {
+            name: get_betterttv_channel(twitch_user_id),
+        };
+
+        Ok(vec![serde_json::to_string(&join_channel)?])
+    }
+
+    fn parse_event(&self, text: &str, twitch_user_id: &str) -> Vec<EmoteUpdate> {
+        let event = match serde_json::from_str::<BetterTtvEvent>(text) {
+            Ok(event) => event,
+            Err(_) => return vec![],
+        };
+
+        let (channel, update) = match event {
+            BetterTtvEvent::EmoteCreate { channel, emote } => {
+                let (code, emote) = map_betterttv_emote(emote);
+                let update = EmoteUpdate::Added {
+                    code,
+                    emote,
+                    actor: None,
+                };
+
+                (channel, update)
+            }
+            BetterTtvEvent::EmoteUpdate { channel, emote } => {
+                let update = EmoteUpdate::Renamed {
+                    emote_id: emote.id,
+                    code: emote.code,
+                    actor: None,
+                };
+
+                (channel, update)
+            }
+            BetterTtvEvent::EmoteDelete { channel, emote_id } => {
+                let update = EmoteUpdate::Removed {
+                    emote_id,
+                    actor: None,
+                };
+
+                (channel, update)
+            }
+            BetterTtvEvent::JoinChannel { .. } => return vec![],
+        };
+
+        if channel != get_betterttv_channel(twitch_user_id) {
+            return vec![];
+        }
+
+        vec![update]
+    }
 }
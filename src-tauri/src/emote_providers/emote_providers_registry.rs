@@ -0,0 +1,305 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use chrono::Utc;
+use gigachat_models::{
+    EmoteFetchHealth, EmoteProviderCapabilities, EmoteProviderHealth, EmoteProviderInfo,
+    EmoteProviderStatus,
+};
+
+use super::EmoteProvider;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmoteFetchScope {
+    Global,
+    Channel,
+}
+
+struct RegisteredProvider {
+    provider: Box<dyn EmoteProvider>,
+    priority: i32,
+}
+
+// Emote providers with explicit priorities and results of their last fetches
+pub struct EmoteProvidersRegistry {
+    // Sorted by priority, lowest first
+    providers: Vec<RegisteredProvider>,
+    health: Mutex<HashMap<&'static str, EmoteProviderHealth>>,
+}
+
+impl EmoteProvidersRegistry {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+            health: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_provider(mut self, provider: Box<dyn EmoteProvider>, priority: i32) -> Self {
+        self.providers
+            .push(RegisteredProvider { provider, priority });
+        self.providers.sort_by_key(|registered| registered.priority);
+
+        self
+    }
+
+    // Enabled providers with the capability, lowest priority first,
+    // so that merging their emotes in order lets higher priority win
+    pub fn get_enabled_providers(
+        &self,
+        disabled_providers: &[String],
+        has_capability: impl Fn(&EmoteProviderCapabilities) -> bool,
+    ) -> Vec<&dyn EmoteProvider> {
+        self.providers
+            .iter()
+            .map(|registered| &*registered.provider)
+            .filter(|provider| {
+                !disabled_providers
+                    .iter()
+                    .any(|disabled| disabled == provider.get_id())
+            })
+            .filter(|provider| has_capability(&provider.get_capabilities()))
+            .collect()
+    }
+
+    // Unknown provider ids lose to all registered providers
+    pub fn get_priority(&self, provider_id: &str) -> i32 {
+        self.providers
+            .iter()
+            .find(|registered| registered.provider.get_id() == provider_id)
+            .map_or(i32::MIN, |registered| registered.priority)
+    }
+
+    // Count of fetched emotes or the error, with the time the request took
+    pub fn report_fetch(
+        &self,
+        provider_id: &'static str,
+        scope: EmoteFetchScope,
+        result: Result<usize, String>,
+        latency: Duration,
+    ) {
+        let latency_ms = Some(latency.as_millis() as u64);
+
+        let fetch_health = match result {
+            Ok(emote_count) => EmoteFetchHealth {
+                status: EmoteProviderStatus::Ok,
+                last_error: None,
+                last_fetch: Some(Utc::now()),
+                emote_count: Some(emote_count as u32),
+                latency_ms,
+            },
+            Err(error) => EmoteFetchHealth {
+                status: EmoteProviderStatus::Failed,
+                last_error: Some(error),
+                last_fetch: Some(Utc::now()),
+                emote_count: None,
+                latency_ms,
+            },
+        };
+
+        let mut health = self.health.lock().unwrap();
+        let provider_health = health.entry(provider_id).or_default();
+
+        match scope {
+            EmoteFetchScope::Global => provider_health.global = fetch_health,
+            EmoteFetchScope::Channel => provider_health.channel = fetch_health,
+        }
+    }
+
+    // All providers, highest priority first
+    pub fn get_providers_info(&self, disabled_providers: &[String]) -> Vec<EmoteProviderInfo> {
+        let health = self.health.lock().unwrap();
+
+        self.providers
+            .iter()
+            .rev()
+            .map(|registered| {
+                let provider = &registered.provider;
+
+                EmoteProviderInfo {
+                    id: provider.get_id().to_string(),
+                    name: provider.get_name().to_string(),
+                    priority: registered.priority,
+                    enabled: !disabled_providers
+                        .iter()
+                        .any(|disabled| disabled == provider.get_id()),
+                    capabilities: provider.get_capabilities(),
+                    health: health.get(provider.get_id()).cloned().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for EmoteProvidersRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::state::saved_emotes_state::EmotesMap;
+
+    struct TestEmoteProvider {
+        id: &'static str,
+        channel_emotes: bool,
+    }
+
+    #[async_trait]
+    impl EmoteProvider for TestEmoteProvider {
+        fn get_id(&self) -> &'static str {
+            self.id
+        }
+
+        fn get_name(&self) -> &'static str {
+            self.id
+        }
+
+        fn get_capabilities(&self) -> EmoteProviderCapabilities {
+            EmoteProviderCapabilities {
+                global_emotes: true,
+                channel_emotes: self.channel_emotes,
+                ..EmoteProviderCapabilities::default()
+            }
+        }
+
+        async fn get_global_emotes(&self) -> anyhow::Result<EmotesMap> {
+            Ok(EmotesMap::new())
+        }
+
+        async fn get_channel_emotes<'a>(&self, _: &'a str) -> anyhow::Result<EmotesMap> {
+            Ok(EmotesMap::new())
+        }
+    }
+
+    fn create_registry() -> EmoteProvidersRegistry {
+        let create_provider = |id, channel_emotes| {
+            Box::new(TestEmoteProvider { id, channel_emotes }) as Box<dyn EmoteProvider>
+        };
+
+        EmoteProvidersRegistry::new()
+            .with_provider(create_provider("low", true), 1)
+            .with_provider(create_provider("high", true), 3)
+            .with_provider(create_provider("global_only", false), 2)
+    }
+
+    fn get_ids(providers: Vec<&dyn EmoteProvider>) -> Vec<&'static str> {
+        providers
+            .into_iter()
+            .map(|provider| provider.get_id())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_enabled_providers() {
+        let registry = create_registry();
+
+        assert_eq!(
+            get_ids(registry.get_enabled_providers(&[], |capabilities| capabilities.global_emotes)),
+            vec!["low", "global_only", "high"]
+        );
+        assert_eq!(
+            get_ids(
+                registry.get_enabled_providers(&["low".to_string()], |capabilities| {
+                    capabilities.channel_emotes
+                })
+            ),
+            vec!["high"]
+        );
+    }
+
+    #[test]
+    fn test_get_priority() {
+        let registry = create_registry();
+
+        assert_eq!(registry.get_priority("high"), 3);
+        assert_eq!(registry.get_priority("unknown"), i32::MIN);
+    }
+
+    #[test]
+    fn test_get_providers_info() {
+        let registry = create_registry();
+        registry.report_fetch(
+            "high",
+            EmoteFetchScope::Global,
+            Err("Timeout".to_string()),
+            Duration::from_millis(5000),
+        );
+        registry.report_fetch(
+            "low",
+            EmoteFetchScope::Global,
+            Ok(42),
+            Duration::from_millis(120),
+        );
+
+        let providers_info = registry.get_providers_info(&["global_only".to_string()]);
+        let ids: Vec<&str> = providers_info.iter().map(|info| info.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["high", "global_only", "low"]);
+        assert_eq!(
+            providers_info[0].health.get_status(),
+            EmoteProviderStatus::Failed
+        );
+        assert_eq!(
+            providers_info[0].health.global.last_error,
+            Some("Timeout".to_string())
+        );
+        assert_eq!(providers_info[0].health.global.emote_count, None);
+        assert_eq!(providers_info[0].health.global.latency_ms, Some(5000));
+        assert!(!providers_info[1].enabled);
+        assert_eq!(
+            providers_info[1].health.get_status(),
+            EmoteProviderStatus::Unknown
+        );
+        assert_eq!(
+            providers_info[2].health.get_status(),
+            EmoteProviderStatus::Ok
+        );
+        assert_eq!(providers_info[2].health.global.emote_count, Some(42));
+        assert_eq!(providers_info[2].health.global.latency_ms, Some(120));
+    }
+
+    #[test]
+    fn test_report_fetch_scopes() {
+        let registry = create_registry();
+        registry.report_fetch(
+            "low",
+            EmoteFetchScope::Global,
+            Ok(50),
+            Duration::from_millis(100),
+        );
+        registry.report_fetch(
+            "low",
+            EmoteFetchScope::Channel,
+            Err("Not found".to_string()),
+            Duration::from_millis(300),
+        );
+
+        let providers_info = registry.get_providers_info(&[]);
+        let health = &providers_info[2].health;
+
+        // Channel failure doesn't hide the successful global fetch
+        assert_eq!(health.global.status, EmoteProviderStatus::Ok);
+        assert_eq!(health.global.emote_count, Some(50));
+        assert_eq!(health.channel.status, EmoteProviderStatus::Failed);
+        assert_eq!(health.channel.last_error, Some("Not found".to_string()));
+        assert_eq!(health.get_status(), EmoteProviderStatus::Failed);
+
+        registry.report_fetch(
+            "low",
+            EmoteFetchScope::Channel,
+            Ok(7),
+            Duration::from_millis(80),
+        );
+
+        let providers_info = registry.get_providers_info(&[]);
+        let health = &providers_info[2].health;
+
+        assert_eq!(health.global.emote_count, Some(50));
+        assert_eq!(health.channel.emote_count, Some(7));
+        assert_eq!(health.get_status(), EmoteProviderStatus::Ok);
+    }
+}
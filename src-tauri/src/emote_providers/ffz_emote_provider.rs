@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
+use gigachat_models::EmoteProviderCapabilities;
 use serde::{Deserialize, Serialize};
 
-use crate::state::saved_emotes_state::EmotesMap;
+use crate::state::saved_emotes_state::{EmoteUrls, EmotesMap, ScaledUrls};
 
 use super::EmoteProvider;
 
@@ -17,6 +18,9 @@ struct FfzEmote {
     pub hidden: bool,
     pub modifier: bool,
     pub urls: HashMap<String, String>,
+    // Present only for animated emotes, `urls` are still images then
+    #[serde(default)]
+    pub animated: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +45,33 @@ fn filter_hidden_emotes(emotes: Vec<FfzEmote>) -> Vec<FfzEmote> {
         .collect::<Vec<FfzEmote>>()
 }
 
+// FFZ urls are keyed by scale, e.g. `"1"`, `"2"` and `"4"`
+fn map_ffz_urls(urls: &HashMap<String, String>) -> ScaledUrls {
+    urls.iter()
+        .filter_map(|(scale, url)| Some((scale.parse().ok()?, url.to_string())))
+        .collect()
+}
+
+fn map_ffz_emote(emote: FfzEmote) -> Option<(String, EmoteUrls)> {
+    let urls = map_ffz_urls(&emote.urls);
+    if urls.is_empty() {
+        return None;
+    }
+
+    let emote_urls = match &emote.animated {
+        Some(animated_urls) => EmoteUrls::new(map_ffz_urls(animated_urls), Some(urls)),
+        None => EmoteUrls::new(urls, None),
+    };
+
+    Some((
+        emote.name,
+        EmoteUrls {
+            emote_id: emote.id.to_string(),
+            ..emote_urls
+        },
+    ))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FfzRoomResponse {
     //pub room
@@ -67,10 +98,23 @@ impl Default for FfzEmoteProvider {
 
 #[async_trait]
 impl EmoteProvider for FfzEmoteProvider {
+    fn get_id(&self) -> &'static str {
+        "ffz"
+    }
+
     fn get_name(&self) -> &'static str {
         "FFZ"
     }
 
+    fn get_capabilities(&self) -> EmoteProviderCapabilities {
+        EmoteProviderCapabilities {
+            global_emotes: true,
+            channel_emotes: true,
+            badges: false,
+            live_updates: false,
+        }
+    }
+
     async fn get_global_emotes(&self) -> anyhow::Result<EmotesMap> {
         let client = self.reqwest_client.clone();
 
@@ -97,15 +141,7 @@ impl EmoteProvider for FfzEmoteProvider {
                     Vec::new()
                 }
             })
-            .flat_map(|emote| {
-                let onex_url = emote.urls.get("1");
-
-                if let Some(url) = onex_url {
-                    Some((emote.name, url.to_string()))
-                } else {
-                    None
-                }
-            })
+            .flat_map(map_ffz_emote)
             .collect();
 
         Ok(emotes)
@@ -129,15 +165,7 @@ impl EmoteProvider for FfzEmoteProvider {
             .sets
             .into_iter()
             .flat_map(|(_, set)| filter_hidden_emotes(set.emoticons))
-            .flat_map(|emote| {
-                let onex_url = emote.urls.get("1");
-
-                if let Some(url) = onex_url {
-                    Some((emote.name, url.to_string()))
-                } else {
-                    None
-                }
-            })
+            .flat_map(map_ffz_emote)
             .collect();
 
         Ok(emotes)
@@ -1,16 +1,76 @@
 use async_trait::async_trait;
+use gigachat_models::EmoteProviderCapabilities;
+use lazy_static::lazy_static;
 
-use crate::state::saved_emotes_state::EmotesMap;
+use crate::state::saved_emotes_state::{EmoteUrls, EmotesMap};
 
 pub mod better_ttv_emote_provider;
+pub mod emote_providers_registry;
 pub mod ffz_emote_provider;
 pub mod seven_tv_emote_provider;
 
+use better_ttv_emote_provider::BetterTtvEmoteProvider;
+use emote_providers_registry::EmoteProvidersRegistry;
+use ffz_emote_provider::FfzEmoteProvider;
+use seven_tv_emote_provider::SevenTvEmoteProvider;
+
+lazy_static! {
+    // Same emote code from several providers resolves to 7TV, then BetterTTV, then FFZ
+    pub static ref EMOTE_PROVIDERS: EmoteProvidersRegistry = EmoteProvidersRegistry::new()
+        .with_provider(Box::<SevenTvEmoteProvider>::default(), 30)
+        .with_provider(Box::<BetterTtvEmoteProvider>::default(), 20)
+        .with_provider(Box::<FfzEmoteProvider>::default(), 10);
+}
+
 #[async_trait]
 pub trait EmoteProvider: Send + Sync {
+    // Stable identifier, used in settings to disable the provider
+    fn get_id(&self) -> &'static str;
+
     fn get_name(&self) -> &'static str;
 
+    fn get_capabilities(&self) -> EmoteProviderCapabilities;
+
     async fn get_global_emotes(&self) -> anyhow::Result<EmotesMap>;
 
     async fn get_channel_emotes<'a>(&self, twitch_user_id: &'a str) -> anyhow::Result<EmotesMap>;
+
+    fn get_live_updates(&self) -> Option<&dyn LiveEmoteProvider> {
+        None
+    }
+}
+
+// Change of channel emotes pushed by a provider. Actor is the user who made it, when known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmoteUpdate {
+    Added {
+        code: String,
+        emote: EmoteUrls,
+        actor: Option<String>,
+    },
+    Removed {
+        emote_id: String,
+        actor: Option<String>,
+    },
+    Renamed {
+        emote_id: String,
+        code: String,
+        actor: Option<String>,
+    },
+}
+
+// Event stream of channel emote changes, for providers with `live_updates` capability
+#[async_trait]
+pub trait LiveEmoteProvider: Send + Sync {
+    // WebSocket endpoint, can be replaced with `GIGACHAT_<PROVIDER ID>_EVENTS_URL` variable
+    fn get_events_url(&self) -> &'static str;
+
+    // Messages sent after connecting to receive changes of the channel
+    async fn get_subscribe_messages<'a>(
+        &self,
+        twitch_user_id: &'a str,
+    ) -> anyhow::Result<Vec<String>>;
+
+    // Changes in a received message, nothing for heartbeats and other channels
+    fn parse_event(&self, text: &str, twitch_user_id: &str) -> Vec<EmoteUpdate>;
 }
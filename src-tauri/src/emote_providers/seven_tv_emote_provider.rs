@@ -1,59 +1,189 @@
 use async_trait::async_trait;
+use gigachat_models::EmoteProviderCapabilities;
 use serde::{Deserialize, Serialize};
 
-use crate::state::saved_emotes_state::EmotesMap;
+use crate::state::saved_emotes_state::{EmoteUrls, EmotesMap};
 
-use super::EmoteProvider;
+use super::{EmoteProvider, EmoteUpdate, LiveEmoteProvider};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct SevenTvEmoteOwnerRole {
-    id: String,
+struct SevenTvImageFile {
     name: String,
-    position: i32,
-    color: i32,
-    allowed: i32,
-    denied: i32,
+    static_name: String,
+    width: i32,
+    height: i32,
+    format: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvImageHost {
+    // Protocol relative, e.g. `//cdn.7tv.app/emote/<id>`
+    url: String,
+    files: Vec<SevenTvImageFile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct SevenTvEmoteOwner {
+struct SevenTvEmoteData {
     id: String,
-    twitch_id: String,
-    login: String,
-    display_name: String,
-    role: SevenTvEmoteOwnerRole,
+    name: String,
+    animated: bool,
+    host: SevenTvImageHost,
 }
 
+// Emote added to an emote set, its name may differ from the original emote name
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SevenTvEmote {
     id: String,
     name: String,
-    owner: SevenTvEmoteOwner,
-    visibility: i32,
-    visibility_simple: Vec<String>,
-    mime: String,
-    status: i32,
-    tags: Vec<String>,
-    width: Vec<i32>,
-    height: Vec<i32>,
-    urls: Vec<Vec<String>>,
-}
-
-fn map_seventv_emotes(emotes: Vec<SevenTvEmote>) -> EmotesMap {
-    emotes
+    data: SevenTvEmoteData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvEmoteSet {
+    id: String,
+    #[serde(default)]
+    emotes: Option<Vec<SevenTvEmote>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvUserResponse {
+    id: String,
+    emote_set: Option<SevenTvEmoteSet>,
+}
+
+// Files are named by scale, e.g. `2x.webp` with `2x_static.webp` still image
+fn get_seventv_file_scale(file: &SevenTvImageFile) -> Option<u32> {
+    file.name.strip_suffix("x.webp")?.parse().ok()
+}
+
+fn map_seventv_emote(emote: SevenTvEmote) -> Option<(String, EmoteUrls)> {
+    let host = &emote.data.host;
+    let files: Vec<(u32, &SevenTvImageFile)> = host
+        .files
+        .iter()
+        .filter(|file| file.format == "WEBP")
+        .filter_map(|file| Some((get_seventv_file_scale(file)?, file)))
+        .collect();
+
+    if files.is_empty() {
+        return None;
+    }
+
+    let urls = files
+        .iter()
+        .map(|(scale, file)| (*scale, format!("https:{}/{}", host.url, file.name)))
+        .collect();
+    let static_urls = emote.data.animated.then(|| {
+        files
+            .iter()
+            .map(|(scale, file)| (*scale, format!("https:{}/{}", host.url, file.static_name)))
+            .collect()
+    });
+
+    Some((
+        emote.name,
+        EmoteUrls {
+            emote_id: emote.id,
+            ..EmoteUrls::new(urls, static_urls)
+        },
+    ))
+}
+
+fn map_seventv_emotes(emote_set: SevenTvEmoteSet) -> EmotesMap {
+    emote_set
+        .emotes
+        .unwrap_or_default()
         .into_iter()
-        .map(|emote| {
-            let url = emote
-                .urls
-                .get(0)
-                .expect("Failed to get at least one emote url");
-            let url = url.get(1).expect("Failed to get 1x emote url");
-
-            (emote.name, url.to_string())
-        })
+        .filter_map(map_seventv_emote)
         .collect()
 }
 
+// Event API opcodes, see https://github.com/SevenTV/EventAPI
+const SEVENTV_DISPATCH_OPCODE: u32 = 0;
+const SEVENTV_SUBSCRIBE_OPCODE: u32 = 35;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvEventMessage {
+    op: u32,
+    #[serde(default)]
+    d: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvActor {
+    display_name: String,
+}
+
+// Emote removed from a set, only ids and names are sent for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvEmoteRef {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvChangeField {
+    key: String,
+    #[serde(default)]
+    value: serde_json::Value,
+    #[serde(default)]
+    old_value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvChangeMap {
+    actor: Option<SevenTvActor>,
+    #[serde(default)]
+    pushed: Vec<SevenTvChangeField>,
+    #[serde(default)]
+    pulled: Vec<SevenTvChangeField>,
+    #[serde(default)]
+    updated: Vec<SevenTvChangeField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SevenTvDispatch {
+    #[serde(rename = "type")]
+    event_type: String,
+    body: SevenTvChangeMap,
+}
+
+fn get_seventv_emote_updates(change_map: SevenTvChangeMap) -> Vec<EmoteUpdate> {
+    let actor = change_map.actor.map(|actor| actor.display_name);
+    let emote_fields =
+        |fields: Vec<SevenTvChangeField>| fields.into_iter().filter(|field| field.key == "emotes");
+
+    let added = emote_fields(change_map.pushed).filter_map(|field| {
+        let emote = serde_json::from_value::<SevenTvEmote>(field.value).ok()?;
+        let (code, emote) = map_seventv_emote(emote)?;
+
+        Some(EmoteUpdate::Added {
+            code,
+            emote,
+            actor: actor.clone(),
+        })
+    });
+    let removed = emote_fields(change_map.pulled).filter_map(|field| {
+        let emote = serde_json::from_value::<SevenTvEmoteRef>(field.old_value).ok()?;
+
+        Some(EmoteUpdate::Removed {
+            emote_id: emote.id,
+            actor: actor.clone(),
+        })
+    });
+    let renamed = emote_fields(change_map.updated).filter_map(|field| {
+        let emote = serde_json::from_value::<SevenTvEmoteRef>(field.value).ok()?;
+
+        Some(EmoteUpdate::Renamed {
+            emote_id: emote.id,
+            code: emote.name,
+            actor: actor.clone(),
+        })
+    });
+
+    added.chain(removed).chain(renamed).collect()
+}
+
 pub struct SevenTvEmoteProvider {
     reqwest_client: reqwest::Client,
 }
@@ -64,7 +194,22 @@ impl SevenTvEmoteProvider {
             reqwest_client: reqwest::Client::new(),
         }
     }
+
+    async fn get_user(&self, twitch_user_id: &str) -> anyhow::Result<SevenTvUserResponse> {
+        let client = self.reqwest_client.clone();
+
+        let user_response = client
+            .get(format!("https://7tv.io/v3/users/twitch/{}", twitch_user_id))
+            .header("Accept", "application/json")
+            .send()
+            .await?
+            .json::<SevenTvUserResponse>()
+            .await?;
+
+        Ok(user_response)
+    }
 }
+
 impl Default for SevenTvEmoteProvider {
     fn default() -> Self {
         Self::new()
@@ -73,38 +218,94 @@ impl Default for SevenTvEmoteProvider {
 
 #[async_trait]
 impl EmoteProvider for SevenTvEmoteProvider {
+    fn get_id(&self) -> &'static str {
+        "seventv"
+    }
+
     fn get_name(&self) -> &'static str {
         "7tv"
     }
 
-    async fn get_channel_emotes<'a>(&self, twitch_user_id: &'a str) -> anyhow::Result<EmotesMap> {
-        let client = self.reqwest_client.clone();
+    fn get_capabilities(&self) -> EmoteProviderCapabilities {
+        EmoteProviderCapabilities {
+            global_emotes: true,
+            channel_emotes: true,
+            badges: false,
+            live_updates: true,
+        }
+    }
 
-        let emotes = client
-            .get(format!(
-                "https://api.7tv.app/v2/users/{}/emotes",
-                twitch_user_id
-            ))
-            .header("Accept", "application/json")
-            .send()
-            .await?
-            .json::<Vec<SevenTvEmote>>()
-            .await?;
+    async fn get_channel_emotes<'a>(&self, twitch_user_id: &'a str) -> anyhow::Result<EmotesMap> {
+        let user_response = self.get_user(twitch_user_id).await?;
 
-        Ok(map_seventv_emotes(emotes))
+        Ok(user_response
+            .emote_set
+            .map(map_seventv_emotes)
+            .unwrap_or_default())
     }
 
     async fn get_global_emotes(&self) -> anyhow::Result<EmotesMap> {
         let client = self.reqwest_client.clone();
 
-        let emotes = client
-            .get("https://api.7tv.app/v2/emotes/global")
+        let emote_set = client
+            .get("https://7tv.io/v3/emote-sets/global")
             .header("Accept", "application/json")
             .send()
             .await?
-            .json::<Vec<SevenTvEmote>>()
+            .json::<SevenTvEmoteSet>()
             .await?;
 
-        Ok(map_seventv_emotes(emotes))
+        Ok(map_seventv_emotes(emote_set))
+    }
+
+    fn get_live_updates(&self) -> Option<&dyn LiveEmoteProvider> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl LiveEmoteProvider for SevenTvEmoteProvider {
+    fn get_events_url(&self) -> &'static str {
+        "wss://events.7tv.io/v3"
+    }
+
+    // Changes are sent for the channel's active emote set, which is fetched for its id
+    async fn get_subscribe_messages<'a>(
+        &self,
+        twitch_user_id: &'a str,
+    ) -> anyhow::Result<Vec<String>> {
+        let user_response = self.get_user(twitch_user_id).await?;
+
+        let messages = user_response
+            .emote_set
+            .map(|emote_set| {
+                let subscribe = serde_json::json!({
+                    "op": SEVENTV_SUBSCRIBE_OPCODE,
+                    "d": {
+                        "type": "emote_set.update",
+                        "condition": { "object_id": emote_set.id },
+                    },
+                });
+
+                subscribe.to_string()
+            })
+            .into_iter()
+            .collect();
+
+        Ok(messages)
+    }
+
+    fn parse_event(&self, text: &str, _twitch_user_id: &str) -> Vec<EmoteUpdate> {
+        let message = match serde_json::from_str::<SevenTvEventMessage>(text) {
+            Ok(message) if message.op == SEVENTV_DISPATCH_OPCODE => message,
+            _ => return vec![],
+        };
+
+        match serde_json::from_value::<SevenTvDispatch>(message.d) {
+            Ok(dispatch) if dispatch.event_type == "emote_set.update" => {
+                get_seventv_emote_updates(dispatch.body)
+            }
+            _ => vec![],
+        }
     }
 }
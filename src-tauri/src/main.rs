@@ -7,35 +7,43 @@ extern crate pretty_env_logger;
 #[macro_use]
 extern crate log;
 
+mod actions;
+mod cli;
 mod commands;
 mod emote_providers;
 mod services;
 mod state;
+mod tts_engines;
 mod utils;
 
 use crate::{
-    commands::{app_ready, lock_ui, open_settings, save_settings, sync_settings, unlock_ui},
-    emote_providers::EmoteProvider,
-    services::EmotesService,
+    cli::CliArgs,
+    commands::{
+        app_ready, copy_to_clipboard, generate_control_api_token, get_chat_stats,
+        get_emote_providers, get_emotes, get_profiles, get_sounds, get_system_fonts, get_theme_css,
+        get_themes, lock_ui, open_emotes, open_settings, play_test_sound, refresh_emotes,
+        save_profile, save_settings, switch_profile, sync_settings, unlock_ui, validate_hotkeys,
+    },
+    services::{
+        ChatEvent, DedupService, EmotesService, HotkeysService, IngestionService,
+        InteractionService, LiveEmotesService, MessagesService, OverlayServerService,
+        OverlayWindowsService, SoundsService, StatsService, ThemeService, TtsService,
+        WindowChatEvent, MAIN_WINDOW_LABEL,
+    },
     state::{
         app_settings_state::{toggle_always_on_top_state, toggle_ui_lock_state},
         current_channel_state::{update_current_channel_state, CurrentChannel},
-        saved_emotes_state::{
-            reset_channel_emotes_state, update_channel_emotes_state, update_global_emotes_state,
-            SavedEmotes,
-        },
+        overlay_windows_state::OverlayWindowsState,
+        saved_emotes_state::{reset_channel_emotes_state, update_channel_id_state, EmotesState},
     },
-    utils::{emit_new_message, emit_toggle_always_on_top, emit_toggle_ui_lock},
+    tts_engines::espeak_tts_engine::EspeakTtsEngine,
+    utils::emit_toggle_ui_lock,
 };
+use clap::Parser;
 use dotenvy::dotenv;
-use gigachat_models::{
-    AppSettings, GigaChatMessage, GigaChatSender, UpdateAlwaysOnTopEventArgs,
-    UpdateIgnoreEventsArgs,
-};
-use lazy_static::{__Deref, lazy_static};
-use services::SettingsService;
+use gigachat_models::{AppSettings, UpdateIgnoreEventsArgs};
 use state::ui_lock_state::UiLockState;
-use std::sync::Mutex;
+use std::{collections::HashMap, path::PathBuf, sync::Mutex, time::Duration};
 use tauri::{
     CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem,
 };
@@ -43,48 +51,81 @@ use tauri_plugin_window_state::{AppHandleExt, StateFlags};
 use twitch_irc::{
     login::StaticLoginCredentials, message::ServerMessage, SecureTCPTransport, TwitchIRCClient,
 };
-use utils::open_settings_window;
-
-lazy_static! {
-    static ref EMOTE_PROVIDERS: Vec<Box<dyn EmoteProvider>> = {
-        vec![
-            Box::<emote_providers::better_ttv_emote_provider::BetterTtvEmoteProvider>::default(),
-            Box::<emote_providers::seven_tv_emote_provider::SevenTvEmoteProvider>::default(),
-            Box::<emote_providers::ffz_emote_provider::FfzEmoteProvider>::default(),
-        ]
-    };
-}
+use utils::{open_emotes_window, open_settings_window};
 
 fn main() {
     dotenv().ok();
     pretty_env_logger::init();
 
+    let cli_args = CliArgs::parse();
+
     //Tray menu setup
     let settings = CustomMenuItem::new("settings", "Settings");
+    let emotes = CustomMenuItem::new("emotes".to_string(), "Emotes");
+    let refresh_emotes_item = CustomMenuItem::new("refresh_emotes".to_string(), "Refresh Emotes");
     let mut lock = CustomMenuItem::new("lock", "Lock");
     lock = lock.selected();
     let always_on_top = CustomMenuItem::new("always_on_top".to_string(), "Always On Top");
+    let tts_skip = CustomMenuItem::new("tts_skip".to_string(), "Skip TTS Message");
+    let tts_clear = CustomMenuItem::new("tts_clear".to_string(), "Clear TTS Queue");
     let quit = CustomMenuItem::new("quit".to_string(), "Quit");
 
     let tray_menu = SystemTrayMenu::new()
         .add_item(settings)
+        .add_item(emotes)
+        .add_item(refresh_emotes_item)
         .add_item(always_on_top)
         .add_item(lock)
         .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(tts_skip)
+        .add_item(tts_clear)
+        .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(quit);
     let system_tray = SystemTray::new().with_menu(tray_menu);
 
     tauri::Builder::default()
-        .manage(Mutex::new(SavedEmotes::default()))
+        // Second launch forwards its arguments to the running app and exits
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            info!("Second instance launched with: {:?}", argv);
+
+            match CliArgs::try_parse_from(argv) {
+                Ok(cli_args) => cli_args.apply_to_running_app(&app.app_handle()),
+                Err(err) => warn!("Failed to parse forwarded arguments: {}", err),
+            }
+        }))
+        .manage(cli_args)
+        .manage(Mutex::new(EmotesState::default()))
         .manage(Mutex::new(CurrentChannel::default()))
+        .manage(OverlayWindowsState::default())
         .manage(UiLockState::new(true))
+        .manage(TtsService::new(Box::<EspeakTtsEngine>::default()))
+        .manage(SoundsService::new())
+        .manage(StatsService::new())
+        .manage(LiveEmotesService::from_env())
+        .manage(OverlayServerService::new())
         .invoke_handler(tauri::generate_handler![
             app_ready,
             save_settings,
             unlock_ui,
             lock_ui,
             open_settings,
-            sync_settings
+            sync_settings,
+            get_themes,
+            get_theme_css,
+            get_system_fonts,
+            get_profiles,
+            save_profile,
+            switch_profile,
+            validate_hotkeys,
+            get_sounds,
+            play_test_sound,
+            generate_control_api_token,
+            get_emote_providers,
+            get_emotes,
+            refresh_emotes,
+            get_chat_stats,
+            open_emotes,
+            copy_to_clipboard
         ])
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| {
@@ -102,37 +143,23 @@ fn main() {
                             },
                         );
                     }
+                    "emotes" => {
+                        open_emotes_window(&app.app_handle());
+                    }
+                    "refresh_emotes" => {
+                        EmotesService::reload_emotes(&app.app_handle());
+                    }
                     "always_on_top" => {
-                        let app_handle = app.app_handle();
-                        let app_settings_state = app_handle.state::<Mutex<AppSettings>>();
-                        let app_settings_state = app_settings_state.lock().unwrap();
-                        let new_always_on_top_state = !app_settings_state.always_on_top;
-                        drop(app_settings_state);
-
-                        toggle_always_on_top_state(&app_handle, new_always_on_top_state);
-
-                        emit_toggle_always_on_top(
-                            &app_handle,
-                            UpdateAlwaysOnTopEventArgs {
-                                always_on_top: new_always_on_top_state,
-                            },
-                        );
+                        actions::toggle_always_on_top(&app.app_handle());
                     }
                     "lock" => {
-                        let app_handle = app.app_handle();
-                        let ui_locked_state = app_handle.state::<UiLockState>();
-                        let ui_locked_state = ui_locked_state.lock().unwrap();
-                        let new_lock_value = !*ui_locked_state;
-                        drop(ui_locked_state);
-
-                        toggle_ui_lock_state(&app_handle, new_lock_value);
-
-                        emit_toggle_ui_lock(
-                            &app_handle,
-                            UpdateIgnoreEventsArgs {
-                                ignore_events: new_lock_value,
-                            },
-                        );
+                        actions::toggle_ui_lock(&app.app_handle());
+                    }
+                    "tts_skip" => {
+                        actions::skip_tts_message(&app.app_handle());
+                    }
+                    "tts_clear" => {
+                        actions::clear_tts_queue(&app.app_handle());
                     }
                     "quit" => {
                         let app_handle = app.app_handle();
@@ -144,16 +171,27 @@ fn main() {
             }
         })
         .setup(|app| {
-            let main_window = app.get_window("main").unwrap();
+            let main_window = app.get_window(MAIN_WINDOW_LABEL).unwrap();
             let _ = main_window.set_ignore_cursor_events(true);
 
             let app_handle = app.handle();
 
-            let settings = SettingsService::get_settings(&app_handle);
-            info!("Startup Settings: {:?}", settings);
+            let cli_args = app.state::<CliArgs>().inner().clone();
+            let settings = cli_args.get_startup_settings(&app_handle);
+            info!("Startup Settings: {}", settings);
 
             app_handle.manage::<Mutex<AppSettings>>(Mutex::new(settings.clone()));
 
+            ThemeService::watch_user_theme(app_handle.clone());
+            HotkeysService::register_hotkeys(&app_handle, &settings.hotkeys);
+            InteractionService::watch(app_handle.clone());
+            if let Err(err) = app_handle
+                .state::<OverlayServerService>()
+                .apply_settings(&app_handle, &settings)
+            {
+                error!("{:?}", err);
+            }
+
             tauri::async_runtime::spawn(async move {
                 let config = twitch_irc::ClientConfig::default();
                 let (mut incoming_messages, client) = twitch_irc::TwitchIRCClient::<
@@ -168,91 +206,185 @@ fn main() {
 
                 let app_handle_clone = app_handle.clone();
                 tokio::spawn(async move {
-                    let global_emotes =
-                        EmotesService::get_global_emotes(EMOTE_PROVIDERS.deref()).await;
-
-                    update_global_emotes_state(&app_handle_clone, global_emotes);
+                    EmotesService::load_global_emotes(&app_handle_clone).await;
                 });
 
+                let messages_sender = MessagesService::start_batching(app_handle.clone());
+
+                // Benchmark scenario: replay recorded chat burst instead of waiting for live chat
+                if let Ok(replay_file) = std::env::var("GIGACHAT_REPLAY_FILE") {
+                    let app_handle = app_handle.clone();
+                    let messages_sender = messages_sender.clone();
+
+                    tokio::spawn(async move {
+                        let result = MessagesService::replay_burst(
+                            &app_handle,
+                            &PathBuf::from(replay_file),
+                            &messages_sender,
+                        )
+                        .await;
+
+                        if let Err(err) = result {
+                            error!("Failed to replay chat burst: {:?}", err);
+                        }
+                    });
+                }
+
                 // Initial app state based on settings
                 if settings.always_on_top {
                     toggle_always_on_top_state(&app_handle, true);
                 }
 
-                if !settings.channel_name.is_empty() {
-                    let _ = client.join(settings.channel_name);
-                } else {
+                // Opens additional overlay windows and joins channels of all of them
+                OverlayWindowsService::apply_settings(&app_handle, &settings);
+
+                if settings.channel_name.is_empty() {
                     open_settings_window(&app_handle);
                     toggle_ui_lock_state(&app_handle, false);
                 }
 
+                if let Some(is_ui_locked) = cli_args.get_lock_state() {
+                    toggle_ui_lock_state(&app_handle, is_ui_locked);
+                }
+
                 let join_handle = tokio::spawn(async move {
+                    // Every overlay window filters and collapses messages by its own settings
+                    let mut ingestion_services: HashMap<String, IngestionService> = HashMap::new();
+                    let mut dedup_services: HashMap<String, DedupService> = HashMap::new();
+
                     while let Some(message) = incoming_messages.recv().await {
                         match message {
                             ServerMessage::RoomState(msg) => {
-                                update_current_channel_state(
+                                let is_main_channel = app_handle
+                                    .state::<Mutex<AppSettings>>()
+                                    .lock()
+                                    .unwrap()
+                                    .channel_name
+                                    == msg.channel_login;
+
+                                if is_main_channel {
+                                    update_current_channel_state(
+                                        &app_handle,
+                                        Some(msg.channel_id.clone()),
+                                        Some(msg.channel_login.clone()),
+                                    );
+                                }
+
+                                reset_channel_emotes_state(&app_handle, &msg.channel_login);
+                                update_channel_id_state(
                                     &app_handle,
-                                    Some(msg.channel_id.clone()),
-                                    Some(msg.channel_login.clone()),
+                                    &msg.channel_login,
+                                    &msg.channel_id,
                                 );
 
-                                reset_channel_emotes_state(&app_handle);
-
                                 let app_handle_clone = app_handle.clone();
                                 tokio::spawn(async move {
-                                    let channel_emotes = EmotesService::get_channel_emotes(
-                                        EMOTE_PROVIDERS.deref(),
+                                    EmotesService::load_channel_emotes(
+                                        &app_handle_clone,
+                                        &msg.channel_login,
                                         &msg.channel_id,
                                     )
                                     .await;
-
-                                    update_channel_emotes_state(&app_handle_clone, channel_emotes);
                                 });
                             }
 
                             ServerMessage::Part(msg) => {
-                                update_current_channel_state(&app_handle, None, None);
-                                reset_channel_emotes_state(&app_handle);
+                                let current_channel_state =
+                                    app_handle.state::<Mutex<CurrentChannel>>();
+                                let is_current_channel =
+                                    current_channel_state.lock().unwrap().channel_login.as_ref()
+                                        == Some(&msg.channel_login);
+
+                                if is_current_channel {
+                                    update_current_channel_state(&app_handle, None, None);
+                                }
+
+                                reset_channel_emotes_state(&app_handle, &msg.channel_login);
+                                app_handle
+                                    .state::<StatsService>()
+                                    .reset_channel(&msg.channel_login);
+                                app_handle
+                                    .state::<LiveEmotesService>()
+                                    .unsubscribe(&msg.channel_login);
 
                                 info!("Part: {:?}", msg);
                             }
 
+                            ServerMessage::UserNotice(msg) => {
+                                app_handle
+                                    .state::<SoundsService>()
+                                    .notify_user_notice(&app_handle, &msg);
+                            }
+
                             ServerMessage::Privmsg(msg) => {
-                                let mut emotes = {
-                                    let emotes_state = app_handle.state::<Mutex<SavedEmotes>>();
-                                    let emotes_state = emotes_state.lock().unwrap();
-                                    (*emotes_state).clone()
-                                };
-
-                                let message_emotes =
-                                    EmotesService::parse_twitch_message_emotes(&msg);
-                                emotes.channel_emotes.extend(message_emotes);
-
-                                let message_with_emotes = EmotesService::replace_message_emotes(
-                                    &emotes,
-                                    &msg.message_text,
+                                // Alerts are played even for messages hidden by ingestion filters
+                                app_handle
+                                    .state::<SoundsService>()
+                                    .notify_message(&app_handle, &msg);
+                                MessagesService::record_stats(&app_handle, &msg);
+
+                                let windows = OverlayWindowsService::get_channel_windows(
+                                    &app_handle,
+                                    &msg.channel_login,
                                 );
 
-                                debug!("message_with_emotes {:?}", message_with_emotes);
-
-                                let color = match msg.name_color {
-                                    Some(color) => color.to_string(),
-                                    None => "#999999".to_string(),
-                                };
-
-                                let chat_msg = GigaChatMessage {
-                                    sender: GigaChatSender::new(
-                                        msg.sender.login,
-                                        msg.sender.name,
-                                        color,
-                                    ),
-                                    message: msg.message_text.to_string(),
-                                    message_html: message_with_emotes,
-                                    message_id: msg.message_id.to_string(),
-                                    timestamp: msg.server_timestamp,
-                                };
-
-                                emit_new_message(chat_msg, &app_handle);
+                                for (window_label, settings) in windows {
+                                    let ingestion_service = ingestion_services
+                                        .entry(window_label.clone())
+                                        .or_insert_with(IngestionService::new);
+
+                                    if !ingestion_service.accept(&msg, &settings) {
+                                        trace!(
+                                            "Skipped message: {} in '{}'",
+                                            msg.message_id,
+                                            window_label
+                                        );
+                                        continue;
+                                    }
+
+                                    if settings.collapse_duplicates {
+                                        let dedup_window = Duration::from_secs(
+                                            settings.duplicate_window_secs as u64,
+                                        );
+                                        let dedup_key = MessagesService::get_dedup_key(
+                                            &app_handle,
+                                            &msg.channel_login,
+                                            &msg.message_text,
+                                        );
+                                        let dedup_service = dedup_services
+                                            .entry(window_label.clone())
+                                            .or_insert_with(DedupService::new);
+
+                                        if let Some(update) = dedup_service.check(
+                                            dedup_key,
+                                            &msg.message_id,
+                                            dedup_window,
+                                        ) {
+                                            let _ = messages_sender.send(WindowChatEvent::new(
+                                                &window_label,
+                                                ChatEvent::MessageUpdated(update),
+                                            ));
+                                            continue;
+                                        }
+                                    }
+
+                                    if window_label == MAIN_WINDOW_LABEL {
+                                        app_handle
+                                            .state::<TtsService>()
+                                            .enqueue_message(&app_handle, &msg);
+                                    }
+
+                                    let chat_msg = MessagesService::create_message(
+                                        &app_handle,
+                                        &msg,
+                                        &settings,
+                                    );
+
+                                    let _ = messages_sender.send(WindowChatEvent::new(
+                                        &window_label,
+                                        ChatEvent::NewMessage(chat_msg),
+                                    ));
+                                }
                             }
                             _ => {
                                 trace!("unmatched: {:?}", message);
@@ -0,0 +1,315 @@
+use std::sync::Mutex;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use gigachat_models::AppSettings;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{actions, services::ProfilesService, state::ui_lock_state::UiLockState};
+
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ControlCommand {
+    GetState,
+    Lock,
+    Unlock,
+    ToggleLock,
+    SetAlwaysOnTop { value: bool },
+    ToggleAlwaysOnTop,
+    SwitchChannel { channel_name: String },
+    SwitchProfile { profile_name: String },
+    ClearChat,
+}
+
+#[derive(Debug, Serialize)]
+struct ControlState {
+    channel_name: String,
+    active_profile: String,
+    profiles: Vec<String>,
+    locked: bool,
+    always_on_top: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ControlResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<ControlState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl From<Result<ControlState, String>> for ControlResponse {
+    fn from(result: Result<ControlState, String>) -> Self {
+        match result {
+            Ok(state) => Self {
+                state: Some(state),
+                error: None,
+            },
+            Err(error) => Self {
+                state: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+// Token is taken from `Authorization: Bearer <token>` header or `token` query parameter,
+// the latter is for WebSocket clients which can't set headers
+fn get_request_token<'a>(headers: &'a HeaderMap, query: &'a TokenQuery) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(query.token.as_deref())
+}
+
+// Requests are rejected until a token is configured
+fn is_authorized(expected_token: &str, request_token: Option<&str>) -> bool {
+    let request_token = match request_token {
+        Some(request_token) => request_token,
+        None => return false,
+    };
+
+    // Compares all bytes, so that response time doesn't reveal the matching prefix
+    !expected_token.is_empty()
+        && expected_token.len() == request_token.len()
+        && expected_token
+            .bytes()
+            .zip(request_token.bytes())
+            .fold(0, |diff, (expected, actual)| diff | (expected ^ actual))
+            == 0
+}
+
+fn check_access(
+    app_handle: &AppHandle,
+    headers: &HeaderMap,
+    query: &TokenQuery,
+) -> Result<(), Response> {
+    let settings_state = app_handle.state::<Mutex<AppSettings>>();
+    let settings = settings_state.lock().unwrap();
+
+    if !settings.control_api_enabled {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    }
+
+    if !is_authorized(
+        &settings.control_api_token,
+        get_request_token(headers, query),
+    ) {
+        return Err(StatusCode::UNAUTHORIZED.into_response());
+    }
+
+    Ok(())
+}
+
+fn get_state(app_handle: &AppHandle) -> ControlState {
+    let settings = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .clone();
+    let locked = *app_handle.state::<UiLockState>().lock().unwrap();
+
+    ControlState {
+        channel_name: settings.channel_name,
+        active_profile: settings.active_profile,
+        profiles: ProfilesService::get_profiles(app_handle),
+        locked,
+        always_on_top: settings.always_on_top,
+    }
+}
+
+fn execute(app_handle: &AppHandle, command: ControlCommand) -> Result<ControlState, String> {
+    info!("Control API command: {:?}", command);
+
+    match command {
+        ControlCommand::GetState => {}
+        ControlCommand::Lock => actions::set_ui_lock(app_handle, true),
+        ControlCommand::Unlock => actions::set_ui_lock(app_handle, false),
+        ControlCommand::ToggleLock => actions::toggle_ui_lock(app_handle),
+        ControlCommand::SetAlwaysOnTop { value } => actions::set_always_on_top(app_handle, value),
+        ControlCommand::ToggleAlwaysOnTop => actions::toggle_always_on_top(app_handle),
+        ControlCommand::SwitchChannel { channel_name } => {
+            actions::switch_channel(app_handle, &channel_name)
+        }
+        ControlCommand::SwitchProfile { profile_name } => {
+            actions::switch_profile(app_handle, &profile_name).map_err(|err| err.to_string())?
+        }
+        ControlCommand::ClearChat => actions::clear_chat(app_handle),
+    }
+
+    Ok(get_state(app_handle))
+}
+
+async fn state_handler(
+    State(app_handle): State<AppHandle>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Response {
+    if let Err(response) = check_access(&app_handle, &headers, &query) {
+        return response;
+    }
+
+    Json(ControlResponse::from(Ok(get_state(&app_handle)))).into_response()
+}
+
+async fn actions_handler(
+    State(app_handle): State<AppHandle>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Json(command): Json<ControlCommand>,
+) -> Response {
+    if let Err(response) = check_access(&app_handle, &headers, &query) {
+        return response;
+    }
+
+    match execute(&app_handle, command) {
+        Ok(state) => Json(ControlResponse::from(Ok(state))).into_response(),
+        Err(error) => (
+            StatusCode::BAD_REQUEST,
+            Json(ControlResponse::from(Err(error))),
+        )
+            .into_response(),
+    }
+}
+
+// Every text message is a command, every command is answered with current state or error
+async fn handle_socket(mut socket: WebSocket, app_handle: AppHandle) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let result = serde_json::from_str::<ControlCommand>(&text)
+            .map_err(|err| format!("Invalid command: {}", err))
+            .and_then(|command| execute(&app_handle, command));
+
+        let response = match serde_json::to_string(&ControlResponse::from(result)) {
+            Ok(response) => response,
+            Err(err) => {
+                error!("Failed to serialize control response: {:?}", err);
+                break;
+            }
+        };
+
+        if socket.send(Message::Text(response)).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn socket_handler(
+    ws: WebSocketUpgrade,
+    State(app_handle): State<AppHandle>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Response {
+    if let Err(response) = check_access(&app_handle, &headers, &query) {
+        return response;
+    }
+
+    ws.on_upgrade(move |socket| handle_socket(socket, app_handle))
+}
+
+// Lets external tools (e.g. Stream Deck scripts) control the app without the tray menu
+pub struct ControlApiService {}
+
+impl ControlApiService {
+    pub fn get_router() -> Router<AppHandle> {
+        Router::new()
+            .route("/state", get(state_handler))
+            .route("/actions", post(actions_handler))
+            .route("/ws", get(socket_handler))
+    }
+
+    pub fn generate_token() -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(TOKEN_LENGTH)
+            .map(char::from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            serde_json::from_str::<ControlCommand>(r#"{ "action": "lock" }"#).unwrap(),
+            ControlCommand::Lock
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlCommand>(
+                r#"{ "action": "switch_channel", "channel_name": "streamer" }"#
+            )
+            .unwrap(),
+            ControlCommand::SwitchChannel {
+                channel_name: "streamer".to_string()
+            }
+        );
+        assert!(serde_json::from_str::<ControlCommand>(r#"{ "action": "quit" }"#).is_err());
+    }
+
+    #[test]
+    fn test_get_request_token() {
+        let mut headers = HeaderMap::new();
+        let query = TokenQuery {
+            token: Some("query_token".to_string()),
+        };
+
+        assert_eq!(get_request_token(&headers, &query), Some("query_token"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer header_token"),
+        );
+
+        assert_eq!(get_request_token(&headers, &query), Some("header_token"));
+        assert_eq!(
+            get_request_token(&HeaderMap::new(), &TokenQuery { token: None }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized("secret", Some("secret")));
+        assert!(!is_authorized("secret", Some("secreT")));
+        assert!(!is_authorized("secret", Some("secret2")));
+        assert!(!is_authorized("secret", None));
+        assert!(!is_authorized("", Some("")));
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = ControlApiService::generate_token();
+
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(token, ControlApiService::generate_token());
+    }
+}
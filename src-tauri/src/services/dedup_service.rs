@@ -0,0 +1,187 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use gigachat_models::UpdateMessageEventArgs;
+
+use crate::utils::INVISIBLE_CHARACTERS;
+
+// How many recent distinct messages are compared with the new one
+const MAX_TRACKED_MESSAGES: usize = 30;
+
+#[derive(Debug)]
+struct TrackedMessage {
+    key: String,
+    message_id: String,
+    repeat_count: u32,
+    last_seen: Instant,
+}
+
+// Collapses copypastas and emote walls repeated by different chatters into a single message
+#[derive(Debug, Default)]
+pub struct DedupService {
+    recent_messages: VecDeque<TrackedMessage>,
+}
+
+impl DedupService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Builds comparison key: emotes are kept as is,
+    // plain text is lowercased and stripped of punctuation
+    pub fn get_key(message: &str, is_emote: impl Fn(&str) -> bool) -> String {
+        message
+            .split_whitespace()
+            .filter_map(|word| {
+                let word = word.trim_matches(&INVISIBLE_CHARACTERS[..]);
+
+                if is_emote(word) {
+                    return Some(word.to_string());
+                }
+
+                let normalized: String = word
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(|c| c.to_lowercase())
+                    .collect();
+
+                (!normalized.is_empty()).then_some(normalized)
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    // Returns updated counter of the first message when the new one repeats it within the window
+    pub fn check(
+        &mut self,
+        key: String,
+        message_id: &str,
+        window: Duration,
+    ) -> Option<UpdateMessageEventArgs> {
+        self.check_at(key, message_id, window, Instant::now())
+    }
+
+    fn check_at(
+        &mut self,
+        key: String,
+        message_id: &str,
+        window: Duration,
+        now: Instant,
+    ) -> Option<UpdateMessageEventArgs> {
+        self.recent_messages
+            .retain(|tracked| now.duration_since(tracked.last_seen) < window);
+
+        if key.is_empty() {
+            return None;
+        }
+
+        if let Some(tracked) = self
+            .recent_messages
+            .iter_mut()
+            .find(|tracked| tracked.key == key)
+        {
+            tracked.repeat_count += 1;
+            tracked.last_seen = now;
+
+            return Some(UpdateMessageEventArgs {
+                message_id: tracked.message_id.clone(),
+                repeat_count: tracked.repeat_count,
+            });
+        }
+
+        if self.recent_messages.len() == MAX_TRACKED_MESSAGES {
+            self.recent_messages.pop_front();
+        }
+
+        self.recent_messages.push_back(TrackedMessage {
+            key,
+            message_id: message_id.to_string(),
+            repeat_count: 1,
+            last_seen: now,
+        });
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    fn get_key(message: &str) -> String {
+        DedupService::get_key(message, |word| word == "KEKW")
+    }
+
+    #[test]
+    fn test_get_key_normalizes_text() {
+        assert_eq!(
+            get_key("What  is THIS?! KEKW \u{E0000}"),
+            get_key("what is this KEKW")
+        );
+        assert_ne!(get_key("what is this KEKW"), get_key("what is this kekw"));
+    }
+
+    #[test]
+    fn test_get_key_strips_invisible_characters() {
+        assert_eq!(get_key("KEKW\u{200B}"), get_key("KEKW"));
+        assert_eq!(get_key("\u{2060}KEKW KEKW"), get_key("KEKW KEKW"));
+    }
+
+    #[test]
+    fn test_check_collapses_duplicates() {
+        let mut dedup_service = DedupService::new();
+        let now = Instant::now();
+
+        assert_eq!(
+            dedup_service.check_at(get_key("KEKW KEKW"), "id1", WINDOW, now),
+            None
+        );
+        assert_eq!(
+            dedup_service.check_at(get_key("hello"), "id2", WINDOW, now),
+            None
+        );
+        assert_eq!(
+            dedup_service.check_at(get_key("KEKW  KEKW"), "id3", WINDOW, now),
+            Some(UpdateMessageEventArgs {
+                message_id: "id1".to_string(),
+                repeat_count: 2
+            })
+        );
+        assert_eq!(
+            dedup_service
+                .check_at(get_key("KEKW KEKW"), "id4", WINDOW, now)
+                .map(|update| update.repeat_count),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_check_forgets_messages_outside_window() {
+        let mut dedup_service = DedupService::new();
+        let now = Instant::now();
+
+        dedup_service.check_at(get_key("hello"), "id1", WINDOW, now);
+
+        assert_eq!(
+            dedup_service.check_at(get_key("hello"), "id2", WINDOW, now + WINDOW),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_ignores_empty_messages() {
+        let mut dedup_service = DedupService::new();
+        let now = Instant::now();
+
+        dedup_service.check_at(get_key("!!!"), "id1", WINDOW, now);
+
+        assert_eq!(
+            dedup_service.check_at(get_key("???"), "id2", WINDOW, now),
+            None
+        );
+    }
+}
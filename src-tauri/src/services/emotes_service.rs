@@ -1,21 +1,248 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, sync::Mutex, time::Instant};
 
 use futures::{future::join_all, Future};
-use twitch_irc::message::PrivmsgMessage;
+use gigachat_models::{
+    AppSettings, EmoteAnimation, EmoteInfo, EmoteProviderInfo, EmoteScale, EmotesGroup,
+};
+use tauri::{AppHandle, Manager};
+use twitch_irc::message::{Emote, PrivmsgMessage};
 
 use crate::{
-    emote_providers::EmoteProvider,
-    state::saved_emotes_state::{EmotesMap, SavedEmotes},
+    emote_providers::{emote_providers_registry::EmoteFetchScope, EmoteProvider, EMOTE_PROVIDERS},
+    services::LiveEmotesService,
+    state::saved_emotes_state::{
+        update_channel_emotes_state, update_global_emotes_state, EmoteUrls, EmotesMap, EmotesState,
+        SavedEmotes, ScaledUrls,
+    },
+    utils::INVISIBLE_CHARACTERS,
 };
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
 enum MessageTag {
     Emote,
     PlainText,
 }
 
+const TWITCH_EMOTE_SCALES: [u32; 3] = [1, 2, 3];
+
+const TWITCH_PROVIDER_ID: &str = "twitch";
+
+// Scale of emote previews in the emote browser
+const PREVIEW_SCALE: u32 = 2;
+
+// Emote found in a message, with the preview image for statistics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageEmote {
+    pub code: String,
+    pub url: String,
+}
+
+pub struct EmoteRenderOptions {
+    pub strip_invisible_characters: bool,
+    pub animation: EmoteAnimation,
+    pub scale: EmoteScale,
+}
+
+impl EmoteRenderOptions {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            strip_invisible_characters: settings.strip_invisible_characters,
+            animation: settings.emote_animation,
+            scale: settings.emote_scale,
+        }
+    }
+}
+
+// Url of the smallest image not smaller than the scale, or the largest one
+fn get_scaled_url(urls: &ScaledUrls, scale: u32) -> Option<&String> {
+    urls.range(scale..)
+        .next()
+        .or_else(|| urls.iter().next_back())
+        .map(|(_, url)| url)
+}
+
+// With auto scale all sizes are listed in `srcset`, so that the browser picks one
+// for the screen scale factor. Emote height is set by styles, relative to the font size
+fn get_image_html(urls: &ScaledUrls, escaped_code: &str, scale: EmoteScale, class: &str) -> String {
+    let fixed_scale = match scale {
+        EmoteScale::Auto => None,
+        EmoteScale::X1 => Some(1),
+        EmoteScale::X2 => Some(2),
+        EmoteScale::X4 => Some(4),
+    };
+
+    let src = match fixed_scale {
+        Some(fixed_scale) => get_scaled_url(urls, fixed_scale),
+        None => urls.values().next(),
+    };
+
+    let class_attribute = if class.is_empty() {
+        "".to_string()
+    } else {
+        format!(" class=\"{}\"", class)
+    };
+
+    let srcset_attribute = if fixed_scale.is_none() && urls.len() > 1 {
+        let srcset = urls
+            .iter()
+            .map(|(scale, url)| format!("{} {}x", url, scale))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        format!(
+            " srcset=\"{}\"",
+            html_escape::encode_double_quoted_attribute(&srcset)
+        )
+    } else {
+        "".to_string()
+    };
+
+    format!(
+        "<img{} src=\"{}\"{} alt=\"{}\" />",
+        class_attribute,
+        html_escape::encode_double_quoted_attribute(src.map_or("", |src| src.as_str())),
+        srcset_attribute,
+        escaped_code
+    )
+}
+
+struct MessageToken<'a> {
+    // Whitespace between the previous token and this one, kept as is
+    whitespace: &'a str,
+    text: &'a str,
+    // Set for Twitch native emotes found by their positions
+    twitch_emote_id: Option<&'a str>,
+}
+
+// Byte ranges of whitespace separated words
+fn push_word_ranges<'a>(
+    ranges: &mut Vec<(usize, usize, Option<&'a str>)>,
+    message: &str,
+    start: usize,
+    end: usize,
+) {
+    let mut word_start: Option<usize> = None;
+
+    for (byte_index, c) in message[start..end].char_indices() {
+        let byte_index = start + byte_index;
+
+        match (c.is_whitespace(), word_start) {
+            (true, Some(word_start_index)) => {
+                ranges.push((word_start_index, byte_index, None));
+                word_start = None;
+            }
+            (false, None) => word_start = Some(byte_index),
+            _ => {}
+        }
+    }
+
+    if let Some(word_start_index) = word_start {
+        ranges.push((word_start_index, end, None));
+    }
+}
+
+// Splits the message into native emotes at exact positions Twitch sends and
+// whitespace separated words between them. Twitch counts positions in Unicode
+// code points, so they are converted to byte indices before slicing.
+// Whitespace is kept with the following token, so joining tokens gives the original message
+// without trailing whitespace
+fn tokenize_message<'a>(message: &'a str, twitch_emotes: &'a [Emote]) -> Vec<MessageToken<'a>> {
+    let byte_indices: Vec<usize> = message
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .chain(std::iter::once(message.len()))
+        .collect();
+
+    let mut sorted_emotes: Vec<&Emote> = twitch_emotes.iter().collect();
+    sorted_emotes.sort_by_key(|emote| emote.char_range.start);
+
+    let mut ranges = Vec::new();
+    let mut text_start = 0;
+
+    for emote in sorted_emotes {
+        let (start, end) = match (
+            byte_indices.get(emote.char_range.start),
+            byte_indices.get(emote.char_range.end),
+        ) {
+            (Some(&start), Some(&end)) if start >= text_start && start < end => (start, end),
+            // Out of the message or overlapping the previous emote
+            _ => continue,
+        };
+
+        push_word_ranges(&mut ranges, message, text_start, start);
+        ranges.push((start, end, Some(emote.id.as_str())));
+
+        text_start = end;
+    }
+
+    push_word_ranges(&mut ranges, message, text_start, message.len());
+
+    let mut previous_end = 0;
+
+    ranges
+        .into_iter()
+        .map(|(start, end, twitch_emote_id)| {
+            let token = MessageToken {
+                whitespace: &message[previous_end..start],
+                text: &message[start..end],
+                twitch_emote_id,
+            };
+            previous_end = end;
+
+            token
+        })
+        .collect()
+}
+
+// Message token with the emote it is rendered as, if any
+struct ResolvedToken<'a> {
+    whitespace: &'a str,
+    text: Cow<'a, str>,
+    emote: Option<Cow<'a, EmoteUrls>>,
+}
+
+// Shared by rendering and statistics, so that both see the same emotes
+fn resolve_message_tokens<'a>(
+    get_emote: impl Fn(&str) -> Option<&'a EmoteUrls>,
+    message: &'a str,
+    twitch_emotes: &'a [Emote],
+    strip_invisible_characters: bool,
+) -> Vec<ResolvedToken<'a>> {
+    tokenize_message(message, twitch_emotes)
+        .into_iter()
+        .filter_map(|token| {
+            let text = match token.twitch_emote_id {
+                None if strip_invisible_characters => {
+                    Cow::Owned(EmotesService::strip_invisible_characters(token.text))
+                }
+                _ => Cow::Borrowed(token.text),
+            };
+
+            // Word consisted of invisible characters only, e.g. ` \u{E0000}` at the end
+            if text.is_empty() {
+                return None;
+            }
+
+            let emote = match token.twitch_emote_id {
+                Some(id) => Some(Cow::Owned(EmotesService::get_twitch_emote(id))),
+                // Third-party emotes only match whole words
+                None => get_emote(&text).map(Cow::Borrowed),
+            };
+
+            Some(ResolvedToken {
+                whitespace: token.whitespace,
+                text,
+                emote,
+            })
+        })
+        .collect()
+}
+
+// Fetches emotes from providers in parallel and merges them in providers order,
+// so that emotes of a later (higher priority) provider replace the ones with the same code
 async fn get_all_provider_emotes<'a, Fut>(
-    providers: &'a [Box<dyn EmoteProvider + 'static>],
+    providers: Vec<&'a dyn EmoteProvider>,
+    scope: EmoteFetchScope,
     request_fn: impl Fn(&'a dyn EmoteProvider) -> Fut,
 ) -> EmotesMap
 where
@@ -23,8 +250,20 @@ where
 {
     let callback_ref = &request_fn;
 
-    let requests = providers.iter().map(|provider| async move {
-        let provider_emotes = callback_ref(&**provider).await;
+    let requests = providers.into_iter().map(|provider| async move {
+        let started_at = Instant::now();
+        let provider_emotes = callback_ref(provider).await;
+
+        EMOTE_PROVIDERS.report_fetch(
+            provider.get_id(),
+            scope,
+            provider_emotes
+                .as_ref()
+                .map(|emotes| emotes.len())
+                .map_err(|err| err.to_string()),
+            started_at.elapsed(),
+        );
+
         if provider_emotes.is_err() {
             error!(
                 "Failed to fetch emotes from {}: {:?}",
@@ -35,7 +274,17 @@ where
             return None;
         }
 
-        let provider_emotes = provider_emotes.unwrap();
+        let provider_emotes: EmotesMap = provider_emotes
+            .unwrap()
+            .into_iter()
+            .map(|(code, emote)| {
+                let emote = EmoteUrls {
+                    provider_id: provider.get_id(),
+                    ..emote
+                };
+                (code, emote)
+            })
+            .collect();
 
         info!(
             "Fetched {} emotes from {}",
@@ -47,135 +296,276 @@ where
         Some(provider_emotes)
     });
 
-    join_all(requests)
-        .await
-        .into_iter()
-        .flatten()
-        .flatten()
-        .collect::<EmotesMap>()
-
-    // The code below should work as well?? but produces higher-ranked lifetime error
-    // in places where this function is used
-    // More info: https://github.com/rust-lang/rust/issues/102211
-    // and https://github.com/rust-lang/rust/issues/71671
-    //
-    // futures::stream::iter(providers)
-    //     .map(|provider| async move {
-    //         let provider_emotes = callback_ref(provider).await;
-    //
-    //         if provider_emotes.is_err() {
-    //             error!("Failed to fetch global emotes from {}", provider.get_name());
-    //             return None;
-    //         }
-    //
-    //         let provider_emotes = provider_emotes.unwrap();
-    //
-    //         info!(
-    //             "Fetched {} global emotes from {}",
-    //             provider_emotes.len(),
-    //             provider.get_name()
-    //         );
-    //         trace!("Emotes list: {:?}", provider_emotes);
-    //
-    //         Some(provider_emotes)
-    //     })
-    //     .buffer_unordered(10)
-    //     .fold(HashMap::new(), |mut acc, provider_emotes| async move {
-    //         if let Some(provider_emotes) = provider_emotes {
-    //             acc.extend(provider_emotes);
-    //         }
-    //
-    //         acc
-    //     })
-    //     .await
+    merge_provider_emotes(join_all(requests).await.into_iter().flatten())
+}
+
+fn merge_provider_emotes(providers_emotes: impl Iterator<Item = EmotesMap>) -> EmotesMap {
+    providers_emotes.fold(EmotesMap::new(), |mut emotes, provider_emotes| {
+        emotes.extend(provider_emotes);
+        emotes
+    })
+}
+
+fn get_disabled_providers(app_handle: &AppHandle) -> Vec<String> {
+    app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .disabled_emote_providers
+        .clone()
 }
 
 pub struct EmotesService {}
 
 impl EmotesService {
-    pub async fn get_global_emotes(
-        providers: &[Box<dyn EmoteProvider + 'static>],
-    ) -> HashMap<String, String> {
+    pub async fn get_global_emotes(providers: Vec<&'static dyn EmoteProvider>) -> EmotesMap {
         info!("Fetching all global emotes");
 
-        get_all_provider_emotes(providers, |provider: &dyn EmoteProvider| {
-            provider.get_global_emotes()
-        })
+        get_all_provider_emotes(
+            providers,
+            EmoteFetchScope::Global,
+            |provider: &dyn EmoteProvider| provider.get_global_emotes(),
+        )
         .await
     }
 
     pub async fn get_channel_emotes(
-        providers: &[Box<dyn EmoteProvider + 'static>],
+        providers: Vec<&'static dyn EmoteProvider>,
         channel_id: &str,
-    ) -> HashMap<String, String> {
+    ) -> EmotesMap {
         info!("Fetching channel emotes for {}", channel_id);
 
-        get_all_provider_emotes(providers, |provider: &dyn EmoteProvider| {
-            provider.get_channel_emotes(channel_id)
-        })
+        get_all_provider_emotes(
+            providers,
+            EmoteFetchScope::Channel,
+            |provider: &dyn EmoteProvider| provider.get_channel_emotes(channel_id),
+        )
         .await
     }
 
+    pub async fn load_global_emotes(app_handle: &AppHandle) {
+        let providers = EMOTE_PROVIDERS
+            .get_enabled_providers(&get_disabled_providers(app_handle), |capabilities| {
+                capabilities.global_emotes
+            });
+        let global_emotes = Self::get_global_emotes(providers).await;
+
+        update_global_emotes_state(app_handle, global_emotes);
+    }
+
+    pub async fn load_channel_emotes(
+        app_handle: &AppHandle,
+        channel_login: &str,
+        channel_id: &str,
+    ) {
+        let disabled_providers = get_disabled_providers(app_handle);
+        let providers = EMOTE_PROVIDERS
+            .get_enabled_providers(&disabled_providers, |capabilities| {
+                capabilities.channel_emotes
+            });
+        let channel_emotes = Self::get_channel_emotes(providers, channel_id).await;
+
+        update_channel_emotes_state(app_handle, channel_login, channel_emotes);
+
+        app_handle.state::<LiveEmotesService>().subscribe(
+            app_handle,
+            channel_login,
+            channel_id,
+            &disabled_providers,
+        );
+    }
+
+    // Fetches global emotes and emotes of all joined channels again,
+    // e.g. after emote providers were enabled or disabled
+    pub fn reload_emotes(app_handle: &AppHandle) {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            Self::refresh_emotes(&app_handle).await;
+        });
+    }
+
+    pub async fn refresh_emotes(app_handle: &AppHandle) {
+        let channel_ids = app_handle
+            .state::<Mutex<EmotesState>>()
+            .lock()
+            .unwrap()
+            .channel_ids
+            .clone();
+
+        Self::load_global_emotes(app_handle).await;
+
+        for (channel_login, channel_id) in channel_ids {
+            Self::load_channel_emotes(app_handle, &channel_login, &channel_id).await;
+        }
+    }
+
+    // `default` format is animated when the emote has an animated version
+    pub fn get_twitch_emote(emote_id: &str) -> EmoteUrls {
+        let get_urls = |format: &str| {
+            TWITCH_EMOTE_SCALES
+                .iter()
+                .map(|scale| {
+                    let url = format!(
+                        "https://static-cdn.jtvnw.net/emoticons/v2/{}/{}/dark/{}.0",
+                        emote_id, format, scale
+                    );
+                    (*scale, url)
+                })
+                .collect()
+        };
+
+        EmoteUrls {
+            provider_id: TWITCH_PROVIDER_ID,
+            ..EmoteUrls::new(get_urls("default"), Some(get_urls("static")))
+        }
+    }
+
+    // Twitch emotes of the message by code, for checks which don't need positions
     pub fn parse_twitch_message_emotes(msg: &PrivmsgMessage) -> EmotesMap {
         msg.emotes
             .iter()
-            .map(|emote| {
-                let emote_url = format!(
-                    "https://static-cdn.jtvnw.net/emoticons/v2/{}/default/dark/1.0",
-                    emote.id.clone()
-                );
+            .map(|emote| (emote.code.clone(), Self::get_twitch_emote(&emote.id)))
+            .collect()
+    }
 
-                (emote.code.clone(), emote_url)
+    // Channel and global emotes grouped by provider, in providers order.
+    // Emotes replaced by a provider with higher priority are not listed
+    pub fn get_emotes_groups(
+        emotes_map: &SavedEmotes,
+        providers: &[EmoteProviderInfo],
+    ) -> Vec<EmotesGroup> {
+        providers
+            .iter()
+            .map(|provider| {
+                let get_emotes_info = |emotes: &EmotesMap, channel: bool| {
+                    emotes
+                        .iter()
+                        .filter(|(_, emote)| emote.provider_id == provider.id)
+                        .filter_map(|(code, emote)| {
+                            Some(EmoteInfo {
+                                code: code.clone(),
+                                url: get_scaled_url(&emote.urls, PREVIEW_SCALE)?.clone(),
+                                channel,
+                            })
+                        })
+                        .collect::<Vec<EmoteInfo>>()
+                };
+
+                let mut emotes = get_emotes_info(&emotes_map.channel_emotes, true);
+                emotes.extend(get_emotes_info(&emotes_map.global_emotes, false));
+                emotes.sort_by_key(|emote| (!emote.channel, emote.code.to_lowercase()));
+
+                EmotesGroup {
+                    provider_id: provider.id.clone(),
+                    provider_name: provider.name.clone(),
+                    emotes,
+                }
             })
+            .filter(|group| !group.emotes.is_empty())
             .collect()
     }
 
-    // Returns message html with replaced empote images
-    pub fn replace_message_emotes(emotes_map: &SavedEmotes, message: &str) -> String {
+    // Emote by code following the precedence of `replace_message_emotes`
+    pub fn find_emote<'a>(
+        emotes_map: &'a SavedEmotes,
+        twitch_emotes: &'a EmotesMap,
+        code: &str,
+    ) -> Option<&'a EmoteUrls> {
+        twitch_emotes
+            .get(code)
+            .or_else(|| emotes_map.get_emote(code))
+    }
+
+    // Finds messages consisting of a single emote repeated several times, e.g. `KEKW KEKW KEKW`
+    pub fn get_repeated_emote<'a>(
+        emotes_map: &SavedEmotes,
+        twitch_emotes: &EmotesMap,
+        message: &'a str,
+    ) -> Option<(&'a str, usize)> {
+        let words: Vec<&str> = message.split_whitespace().collect();
+        let first_word = *words.first()?;
+
+        let is_emote = Self::find_emote(emotes_map, twitch_emotes, first_word).is_some();
+
+        if words.len() < 2 || !is_emote || words.iter().any(|word| *word != first_word) {
+            return None;
+        }
+
+        Some((first_word, words.len()))
+    }
+
+    pub fn get_emote_html(emote: &EmoteUrls, code: &str, options: &EmoteRenderOptions) -> String {
+        let code = html_escape::encode_double_quoted_attribute(code);
+
+        match (options.animation, &emote.static_urls) {
+            (EmoteAnimation::Static, Some(static_urls)) => format!(
+                "<div class=\"d-inline gc-emote\">{}</div>",
+                get_image_html(static_urls, &code, options.scale, "")
+            ),
+            // Both images are rendered, styles show the animated one while the emote is hovered
+            (EmoteAnimation::OnHover, Some(static_urls)) => format!(
+                "<div class=\"d-inline gc-emote gc-emote-hover\">{}{}</div>",
+                get_image_html(static_urls, &code, options.scale, "gc-emote-static"),
+                get_image_html(&emote.urls, &code, options.scale, "gc-emote-animated")
+            ),
+            _ => format!(
+                "<div class=\"d-inline gc-emote\">{}</div>",
+                get_image_html(&emote.urls, &code, options.scale, "")
+            ),
+        }
+    }
+
+    // Returns message html with replaced emote images.
+    // Emote precedence, from highest to lowest:
+    // 1. Twitch native emotes, found by positions Twitch sends with the message,
+    //    so that a third-party emote with the same code can't replace them
+    // 2. Third-party channel emotes, so that channel can override a global code
+    // 3. Third-party global emotes
+    pub fn replace_message_emotes(
+        emotes_map: &SavedEmotes,
+        message: &str,
+        twitch_emotes: &[Emote],
+        options: &EmoteRenderOptions,
+    ) -> String {
         let mut html_str = "".to_string();
         let mut last_tag: Option<MessageTag> = None;
 
-        message.split_whitespace().for_each(|word| {
-            let mut found_emote = emotes_map.global_emotes.get(word);
-
-            if found_emote.is_none() {
-                found_emote = emotes_map.channel_emotes.get(word);
-            }
+        let tokens = resolve_message_tokens(
+            |code| emotes_map.get_emote(code),
+            message,
+            twitch_emotes,
+            options.strip_invisible_characters,
+        );
 
-            if let Some(emote_url) = found_emote {
-                let pre_str: &str = last_tag.clone().map_or("", |t| {
-                    if t == MessageTag::PlainText {
-                        " </span>"
-                    } else {
-                        "<span> </span>"
+        for token in tokens {
+            if let Some(emote) = &token.emote {
+                match last_tag {
+                    Some(MessageTag::PlainText) => {
+                        html_str.push_str(&format!("{}</span>", token.whitespace))
                     }
-                });
+                    _ if !token.whitespace.is_empty() => html_str.push_str(&format!(
+                        "<span class=\"gc-text\">{}</span>",
+                        token.whitespace
+                    )),
+                    _ => {}
+                }
 
-                html_str.push_str(&format!(
-                    "{}<div class=\"d-inline\"><img src=\"{}\" alt=\"{}\" /></div>",
-                    pre_str, emote_url, word
-                ));
+                html_str.push_str(&Self::get_emote_html(emote, &token.text, options));
 
                 last_tag = Some(MessageTag::Emote);
             } else {
-                let mut pre_str: String = last_tag.clone().map_or("<span>".to_string(), |t| {
-                    if t == MessageTag::Emote {
-                        "<span>".to_string()
-                    } else {
-                        "".to_string()
+                match last_tag {
+                    Some(MessageTag::PlainText) => html_str.push_str(token.whitespace),
+                    _ => {
+                        html_str.push_str(&format!("<span class=\"gc-text\">{}", token.whitespace))
                     }
-                });
-
-                if last_tag.is_some() {
-                    pre_str.push(' ');
                 }
 
-                let escaped_word = html_escape::encode_text(word);
-                html_str.push_str(&format!("{}{}", pre_str, escaped_word));
+                html_str.push_str(&html_escape::encode_text(&token.text));
 
                 last_tag = Some(MessageTag::PlainText);
             }
-        });
+        }
 
         if let Some(MessageTag::PlainText) = last_tag {
             html_str.push_str("</span>");
@@ -183,6 +573,39 @@ impl EmotesService {
 
         html_str
     }
+
+    // Emotes which `replace_message_emotes` renders in the message, in order of appearance.
+    // Takes a lookup instead of `SavedEmotes`, so that callers can resolve emotes
+    // under the emotes state lock without cloning the maps
+    pub fn get_message_emotes<'a>(
+        get_emote: impl Fn(&str) -> Option<&'a EmoteUrls>,
+        message: &str,
+        twitch_emotes: &[Emote],
+        strip_invisible_characters: bool,
+    ) -> Vec<MessageEmote> {
+        resolve_message_tokens(
+            get_emote,
+            message,
+            twitch_emotes,
+            strip_invisible_characters,
+        )
+        .into_iter()
+        .filter_map(|token| {
+            let url = get_scaled_url(&token.emote?.urls, PREVIEW_SCALE)?.clone();
+
+            Some(MessageEmote {
+                code: token.text.into_owned(),
+                url,
+            })
+        })
+        .collect()
+    }
+
+    pub fn strip_invisible_characters(text: &str) -> String {
+        text.chars()
+            .filter(|c| !INVISIBLE_CHARACTERS.contains(c))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -190,15 +613,47 @@ mod tests {
     use std::collections::HashMap;
 
     use super::*;
+    use crate::utils::test_utils::create_privmsg;
+
+    fn create_emote_urls(url: &str) -> EmoteUrls {
+        EmoteUrls::new(ScaledUrls::from([(1, url.to_string())]), None)
+    }
 
     fn get_emotes_map() -> EmotesMap {
         let mut emotes_map = HashMap::new();
-        emotes_map.insert("Kappa".to_string(), "_kappa_url_".to_string());
-        emotes_map.insert("Pog".to_string(), "_pog_url_".to_string());
+        emotes_map.insert("Kappa".to_string(), create_emote_urls("_kappa_url_"));
+        emotes_map.insert("Pog".to_string(), create_emote_urls("_pog_url_"));
 
         emotes_map
     }
 
+    // Fixed scale keeps expected html without `srcset`
+    fn get_options() -> EmoteRenderOptions {
+        EmoteRenderOptions {
+            scale: EmoteScale::X1,
+            ..EmoteRenderOptions::from_settings(&AppSettings::default())
+        }
+    }
+
+    #[test]
+    fn test_merge_provider_emotes_later_provider_wins() {
+        let low_priority_emotes = EmotesMap::from([
+            ("Kappa".to_string(), create_emote_urls("_ffz_kappa_url_")),
+            ("Pog".to_string(), create_emote_urls("_ffz_pog_url_")),
+        ]);
+        let high_priority_emotes =
+            EmotesMap::from([("Kappa".to_string(), create_emote_urls("_7tv_kappa_url_"))]);
+
+        let emotes =
+            merge_provider_emotes(vec![low_priority_emotes, high_priority_emotes].into_iter());
+
+        assert_eq!(
+            emotes.get("Kappa"),
+            Some(&create_emote_urls("_7tv_kappa_url_"))
+        );
+        assert_eq!(emotes.get("Pog"), Some(&create_emote_urls("_ffz_pog_url_")));
+    }
+
     fn get_saved_emotes() -> SavedEmotes {
         SavedEmotes {
             global_emotes: get_emotes_map(),
@@ -211,11 +666,12 @@ mod tests {
         let message = "hello there Kappa";
         let emotes_map = get_saved_emotes();
 
-        let result = EmotesService::replace_message_emotes(&emotes_map, message);
+        let result =
+            EmotesService::replace_message_emotes(&emotes_map, message, &[], &get_options());
 
         assert_eq!(
             result,
-            "<span>hello there </span><div class=\"d-inline\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
+            "<span class=\"gc-text\">hello there </span><div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
         );
     }
 
@@ -225,11 +681,12 @@ mod tests {
 
         let message = "Kappa";
 
-        let result = EmotesService::replace_message_emotes(&emotes_map, message);
+        let result =
+            EmotesService::replace_message_emotes(&emotes_map, message, &[], &get_options());
 
         assert_eq!(
             result,
-            "<div class=\"d-inline\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
+            "<div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
         );
     }
 
@@ -239,11 +696,12 @@ mod tests {
 
         let message = "Kappa hello there";
 
-        let result = EmotesService::replace_message_emotes(&emotes_map, message);
+        let result =
+            EmotesService::replace_message_emotes(&emotes_map, message, &[], &get_options());
 
         assert_eq!(
             result,
-            "<div class=\"d-inline\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div><span> hello there</span>"
+            "<div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div><span class=\"gc-text\"> hello there</span>"
         );
     }
 
@@ -253,11 +711,466 @@ mod tests {
 
         let message = "Kappa Pog";
 
-        let result = EmotesService::replace_message_emotes(&emotes_map, message);
+        let result =
+            EmotesService::replace_message_emotes(&emotes_map, message, &[], &get_options());
+
+        assert_eq!(
+            result,
+            "<div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div><span class=\"gc-text\"> </span><div class=\"d-inline gc-emote\"><img src=\"_pog_url_\" alt=\"Pog\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_get_repeated_emote() {
+        let emotes_map = get_saved_emotes();
+
+        assert_eq!(
+            EmotesService::get_repeated_emote(&emotes_map, &EmotesMap::new(), "Kappa  Kappa Kappa"),
+            Some(("Kappa", 3))
+        );
+        assert_eq!(
+            EmotesService::get_repeated_emote(&emotes_map, &EmotesMap::new(), "Kappa"),
+            None
+        );
+        assert_eq!(
+            EmotesService::get_repeated_emote(&emotes_map, &EmotesMap::new(), "Kappa Pog Kappa"),
+            None
+        );
+        assert_eq!(
+            EmotesService::get_repeated_emote(&emotes_map, &EmotesMap::new(), "hello hello"),
+            None
+        );
+    }
+
+    fn create_twitch_emotes(message: &str, emotes_tag: &str) -> Vec<Emote> {
+        create_privmsg(&format!("emotes={}", emotes_tag), message).emotes
+    }
+
+    fn get_conflicting_emotes() -> SavedEmotes {
+        SavedEmotes {
+            global_emotes: get_emotes_map(),
+            channel_emotes: HashMap::from([(
+                "Kappa".to_string(),
+                create_emote_urls("_channel_kappa_url_"),
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_replace_message_emotes_channel_emote_overrides_global() {
+        let result = EmotesService::replace_message_emotes(
+            &get_conflicting_emotes(),
+            "Kappa",
+            &[],
+            &get_options(),
+        );
+
+        assert_eq!(
+            result,
+            "<div class=\"d-inline gc-emote\"><img src=\"_channel_kappa_url_\" alt=\"Kappa\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_replace_message_emotes_twitch_emote_overrides_third_party() {
+        let message = "Kappa Pog";
+        let twitch_emotes = create_twitch_emotes(message, "25:0-4");
+
+        let result = EmotesService::replace_message_emotes(
+            &get_conflicting_emotes(),
+            message,
+            &twitch_emotes,
+            &get_options(),
+        );
+
+        assert_eq!(
+            result,
+            "<div class=\"d-inline gc-emote\"><img src=\"https://static-cdn.jtvnw.net/emoticons/v2/25/default/dark/1.0\" alt=\"Kappa\" /></div><span class=\"gc-text\"> </span><div class=\"d-inline gc-emote\"><img src=\"_pog_url_\" alt=\"Pog\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_replace_message_emotes_twitch_emote_by_position() {
+        // Only the second word is a Twitch emote, e.g. the first one is a part of a link text
+        let message = "😀 Kappa Kappa";
+        let twitch_emotes = create_twitch_emotes(message, "25:8-12");
+
+        let result = EmotesService::replace_message_emotes(
+            &SavedEmotes::default(),
+            message,
+            &twitch_emotes,
+            &get_options(),
+        );
+
+        assert_eq!(
+            result,
+            "<span class=\"gc-text\">😀 Kappa </span><div class=\"d-inline gc-emote\"><img src=\"https://static-cdn.jtvnw.net/emoticons/v2/25/default/dark/1.0\" alt=\"Kappa\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_get_message_emotes() {
+        let message = "Kappa Pog hello Pog\u{E0000}";
+        let twitch_emotes = create_twitch_emotes(message, "25:0-4");
+
+        let emotes_map = get_conflicting_emotes();
+        let result = EmotesService::get_message_emotes(
+            |code| emotes_map.get_emote(code),
+            message,
+            &twitch_emotes,
+            true,
+        );
 
         assert_eq!(
             result,
-            "<div class=\"d-inline\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div><span> </span><div class=\"d-inline\"><img src=\"_pog_url_\" alt=\"Pog\" /></div>"
+            vec![
+                MessageEmote {
+                    code: "Kappa".to_string(),
+                    url: "https://static-cdn.jtvnw.net/emoticons/v2/25/default/dark/2.0"
+                        .to_string(),
+                },
+                MessageEmote {
+                    code: "Pog".to_string(),
+                    url: "_pog_url_".to_string(),
+                },
+                MessageEmote {
+                    code: "Pog".to_string(),
+                    url: "_pog_url_".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_emote_precedence() {
+        let emotes_map = get_conflicting_emotes();
+        let twitch_emotes =
+            EmotesMap::from([("Kappa".to_string(), create_emote_urls("_twitch_kappa_url_"))]);
+
+        assert_eq!(
+            EmotesService::find_emote(&emotes_map, &twitch_emotes, "Kappa"),
+            Some(&create_emote_urls("_twitch_kappa_url_"))
+        );
+        assert_eq!(
+            EmotesService::find_emote(&emotes_map, &EmotesMap::new(), "Kappa"),
+            Some(&create_emote_urls("_channel_kappa_url_"))
+        );
+        assert_eq!(
+            EmotesService::find_emote(&emotes_map, &EmotesMap::new(), "Pog"),
+            Some(&create_emote_urls("_pog_url_"))
+        );
+    }
+
+    fn create_emote(id: &str, char_range: std::ops::Range<usize>, code: &str) -> Emote {
+        Emote {
+            id: id.to_string(),
+            char_range,
+            code: code.to_string(),
+        }
+    }
+
+    fn get_twitch_emote_html(id: &str, code: &str) -> String {
+        EmotesService::get_emote_html(&EmotesService::get_twitch_emote(id), code, &get_options())
+    }
+
+    #[test]
+    fn test_replace_message_emotes_tricky_messages() {
+        let kappa = get_twitch_emote_html("25", "Kappa");
+        let smile = get_twitch_emote_html("1", ":)");
+        let pog = "<div class=\"d-inline gc-emote\"><img src=\"_pog_url_\" alt=\"Pog\" /></div>";
+
+        let corpus = vec![
+            // Punctuation right after a native emote
+            (
+                "Kappa, hi",
+                vec![create_emote("25", 0..5, "Kappa")],
+                format!("{}<span class=\"gc-text\">, hi</span>", kappa),
+            ),
+            // Native emote glued to text, third-party emotes only match whole words
+            (
+                "hi:) Pog,",
+                vec![create_emote("1", 2..4, ":)")],
+                format!(
+                    "<span class=\"gc-text\">hi</span>{}<span class=\"gc-text\"> Pog,</span>",
+                    smile
+                ),
+            ),
+            // Adjacent native emotes
+            (
+                "KappaKappa Pog",
+                vec![
+                    create_emote("25", 5..10, "Kappa"),
+                    create_emote("25", 0..5, "Kappa"),
+                ],
+                format!("{}{}<span class=\"gc-text\"> </span>{}", kappa, kappa, pog),
+            ),
+            // Characters outside of the Basic Multilingual Plane before an emote
+            (
+                "😀😀 Kappa",
+                vec![create_emote("25", 3..8, "Kappa")],
+                format!("<span class=\"gc-text\">😀😀 </span>{}", kappa),
+            ),
+            // Emoji joined with zero width joiners are several code points
+            (
+                "👨‍👩‍👧 Kappa",
+                vec![create_emote("25", 6..11, "Kappa")],
+                format!("<span class=\"gc-text\">👨‍👩‍👧 </span>{}", kappa),
+            ),
+            // Multi-byte text
+            (
+                "привет Kappa <3",
+                vec![create_emote("25", 7..12, "Kappa")],
+                format!(
+                    "<span class=\"gc-text\">привет </span>{}<span class=\"gc-text\"> &lt;3</span>",
+                    kappa
+                ),
+            ),
+            // Invalid positions are ignored instead of panicking
+            (
+                "Kappa",
+                vec![
+                    create_emote("25", 3..20, "Kappa"),
+                    create_emote("25", 0..5, "Kappa"),
+                    create_emote("25", 2..4, "pp"),
+                ],
+                kappa.clone(),
+            ),
+        ];
+
+        for (message, twitch_emotes, expected) in corpus {
+            assert_eq!(
+                EmotesService::replace_message_emotes(
+                    &get_conflicting_emotes(),
+                    message,
+                    &twitch_emotes,
+                    &get_options()
+                ),
+                expected,
+                "message: {}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_tokenize_message() {
+        let twitch_emotes = vec![create_emote("25", 3..8, "Kappa")];
+        let tokens: Vec<(&str, &str, Option<&str>)> =
+            tokenize_message(" 😀 Kappa,  hi ", &twitch_emotes)
+                .into_iter()
+                .map(|token| (token.whitespace, token.text, token.twitch_emote_id))
+                .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                (" ", "😀", None),
+                (" ", "Kappa", Some("25")),
+                ("", ",", None),
+                ("  ", "hi", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_message_emotes_preserves_whitespace() {
+        let result = EmotesService::replace_message_emotes(
+            &SavedEmotes::new(get_emotes_map(), HashMap::new()),
+            "  /\\_/\\   Kappa\t  Pog   <3",
+            &[],
+            &get_options(),
+        );
+
+        assert_eq!(
+            result,
+            "<span class=\"gc-text\">  /\\_/\\   </span><div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div><span class=\"gc-text\">\t  </span><div class=\"d-inline gc-emote\"><img src=\"_pog_url_\" alt=\"Pog\" /></div><span class=\"gc-text\">   &lt;3</span>"
+        );
+    }
+
+    #[test]
+    fn test_replace_message_emotes_strips_invisible_characters() {
+        let emotes_map = SavedEmotes::new(get_emotes_map(), HashMap::new());
+        let message = "hi Kappa\u{E0000} \u{E0000}";
+
+        assert_eq!(
+            EmotesService::replace_message_emotes(&emotes_map, message, &[], &get_options()),
+            "<span class=\"gc-text\">hi </span><div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
+        );
+        assert_eq!(
+            EmotesService::replace_message_emotes(
+                &emotes_map,
+                message,
+                &[],
+                &EmoteRenderOptions {
+                    strip_invisible_characters: false,
+                    ..get_options()
+                }
+            ),
+            "<span class=\"gc-text\">hi Kappa\u{E0000} \u{E0000}</span>"
+        );
+    }
+
+    #[test]
+    fn test_strip_invisible_characters_keeps_flag_emoji() {
+        let flag = "🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}";
+
+        assert_eq!(
+            EmotesService::strip_invisible_characters(&format!("{}\u{E0000}", flag)),
+            flag
+        );
+    }
+
+    #[test]
+    fn test_get_emote_html_animation() {
+        let animated_emote = EmoteUrls::new(
+            ScaledUrls::from([(1, "_animated_url_".to_string())]),
+            Some(ScaledUrls::from([(1, "_static_url_".to_string())])),
+        );
+        let static_emote = create_emote_urls("_kappa_url_");
+        let get_html = |emote: &EmoteUrls, code: &str, animation: EmoteAnimation| {
+            EmotesService::get_emote_html(
+                emote,
+                code,
+                &EmoteRenderOptions {
+                    animation,
+                    ..get_options()
+                },
+            )
+        };
+
+        assert_eq!(
+            get_html(&animated_emote, "catJAM", EmoteAnimation::Animated),
+            "<div class=\"d-inline gc-emote\"><img src=\"_animated_url_\" alt=\"catJAM\" /></div>"
+        );
+        assert_eq!(
+            get_html(&animated_emote, "catJAM", EmoteAnimation::Static),
+            "<div class=\"d-inline gc-emote\"><img src=\"_static_url_\" alt=\"catJAM\" /></div>"
+        );
+        assert_eq!(
+            get_html(&animated_emote, "catJAM", EmoteAnimation::OnHover),
+            "<div class=\"d-inline gc-emote gc-emote-hover\"><img class=\"gc-emote-static\" src=\"_static_url_\" alt=\"catJAM\" /><img class=\"gc-emote-animated\" src=\"_animated_url_\" alt=\"catJAM\" /></div>"
+        );
+        assert_eq!(
+            get_html(&static_emote, "Kappa", EmoteAnimation::OnHover),
+            "<div class=\"d-inline gc-emote\"><img src=\"_kappa_url_\" alt=\"Kappa\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_get_scaled_url() {
+        let urls = ScaledUrls::from([
+            (1, "_1x_".to_string()),
+            (2, "_2x_".to_string()),
+            (3, "_3x_".to_string()),
+        ]);
+
+        assert_eq!(get_scaled_url(&urls, 1).unwrap(), "_1x_");
+        assert_eq!(get_scaled_url(&urls, 2).unwrap(), "_2x_");
+        assert_eq!(get_scaled_url(&urls, 4).unwrap(), "_3x_");
+        assert_eq!(
+            get_scaled_url(&ScaledUrls::from([(2, "_2x_".to_string())]), 1).unwrap(),
+            "_2x_"
+        );
+    }
+
+    #[test]
+    fn test_get_emote_html_scale() {
+        let emote = EmoteUrls::new(
+            ScaledUrls::from([
+                (1, "_1x_".to_string()),
+                (2, "_2x_".to_string()),
+                (4, "_4x_".to_string()),
+            ]),
+            None,
+        );
+        let get_html = |scale: EmoteScale| {
+            EmotesService::get_emote_html(
+                &emote,
+                "Kappa",
+                &EmoteRenderOptions {
+                    scale,
+                    ..get_options()
+                },
+            )
+        };
+
+        assert_eq!(
+            get_html(EmoteScale::Auto),
+            "<div class=\"d-inline gc-emote\"><img src=\"_1x_\" srcset=\"_1x_ 1x, _2x_ 2x, _4x_ 4x\" alt=\"Kappa\" /></div>"
+        );
+        assert_eq!(
+            get_html(EmoteScale::X4),
+            "<div class=\"d-inline gc-emote\"><img src=\"_4x_\" alt=\"Kappa\" /></div>"
+        );
+    }
+
+    #[test]
+    fn test_get_emotes_groups() {
+        let create_provider_emote = |provider_id: &'static str, url: &str| EmoteUrls {
+            provider_id,
+            ..create_emote_urls(url)
+        };
+        let create_provider_info = |id: &str, name: &str| EmoteProviderInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            priority: 0,
+            enabled: true,
+            capabilities: Default::default(),
+            health: Default::default(),
+        };
+
+        let emotes_map = SavedEmotes::new(
+            EmotesMap::from([
+                (
+                    "catJAM".to_string(),
+                    create_provider_emote("seventv", "_catjam_url_"),
+                ),
+                (
+                    "Kappa".to_string(),
+                    create_provider_emote("ffz", "_kappa_url_"),
+                ),
+                ("LUL".to_string(), create_provider_emote("ffz", "_lul_url_")),
+            ]),
+            EmotesMap::from([(
+                "Pog".to_string(),
+                create_provider_emote("seventv", "_pog_url_"),
+            )]),
+        );
+        let providers = vec![
+            create_provider_info("seventv", "7tv"),
+            create_provider_info("betterttv", "BetterTTV"),
+            create_provider_info("ffz", "FFZ"),
+        ];
+
+        let groups = EmotesService::get_emotes_groups(&emotes_map, &providers);
+        let groups: Vec<(&str, Vec<(&str, &str, bool)>)> = groups
+            .iter()
+            .map(|group| {
+                let emotes = group
+                    .emotes
+                    .iter()
+                    .map(|emote| (emote.code.as_str(), emote.url.as_str(), emote.channel))
+                    .collect();
+
+                (group.provider_name.as_str(), emotes)
+            })
+            .collect();
+
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "7tv",
+                    vec![
+                        ("Pog", "_pog_url_", true),
+                        ("catJAM", "_catjam_url_", false)
+                    ]
+                ),
+                (
+                    "FFZ",
+                    vec![("Kappa", "_kappa_url_", false), ("LUL", "_lul_url_", false)]
+                ),
+            ]
         );
     }
 }
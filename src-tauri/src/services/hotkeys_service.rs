@@ -0,0 +1,259 @@
+use gigachat_models::{HotkeyConflict, HotkeySettings};
+use tauri::{AppHandle, GlobalShortcutManager};
+
+use crate::actions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    ToggleLock,
+    ToggleVisibility,
+    ToggleAlwaysOnTop,
+    ClearChat,
+    NextProfile,
+}
+
+impl HotkeyAction {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleLock => "Lock / Unlock",
+            HotkeyAction::ToggleVisibility => "Show / Hide",
+            HotkeyAction::ToggleAlwaysOnTop => "Always On Top",
+            HotkeyAction::ClearChat => "Clear Chat",
+            HotkeyAction::NextProfile => "Next Profile",
+        }
+    }
+
+    fn run(&self, app_handle: &AppHandle) {
+        match self {
+            HotkeyAction::ToggleLock => actions::toggle_ui_lock(app_handle),
+            HotkeyAction::ToggleVisibility => actions::toggle_overlay_visibility(app_handle),
+            HotkeyAction::ToggleAlwaysOnTop => actions::toggle_always_on_top(app_handle),
+            HotkeyAction::ClearChat => actions::clear_chat(app_handle),
+            HotkeyAction::NextProfile => actions::switch_to_next_profile(app_handle),
+        }
+    }
+}
+
+fn get_hotkey_bindings(hotkeys: &HotkeySettings) -> Vec<(HotkeyAction, &str)> {
+    vec![
+        (HotkeyAction::ToggleLock, hotkeys.toggle_lock.as_str()),
+        (
+            HotkeyAction::ToggleVisibility,
+            hotkeys.toggle_visibility.as_str(),
+        ),
+        (
+            HotkeyAction::ToggleAlwaysOnTop,
+            hotkeys.toggle_always_on_top.as_str(),
+        ),
+        (HotkeyAction::ClearChat, hotkeys.clear_chat.as_str()),
+        (HotkeyAction::NextProfile, hotkeys.next_profile.as_str()),
+    ]
+    .into_iter()
+    .filter(|(_, accelerator)| !accelerator.trim().is_empty())
+    .collect()
+}
+
+fn create_conflict(action: HotkeyAction, accelerator: &str, reason: &str) -> HotkeyConflict {
+    HotkeyConflict {
+        action: action.get_name().to_string(),
+        accelerator: accelerator.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+pub struct HotkeysService {}
+
+impl HotkeysService {
+    // Normalizes accelerator so that e.g. `shift+alt+l` and `Alt+Shift+L` compare equal
+    pub fn normalize_accelerator(accelerator: &str) -> String {
+        Self::normalize_platform_accelerator(accelerator, cfg!(target_os = "macos"))
+    }
+
+    // `CmdOrCtrl` is Cmd on macOS and Ctrl elsewhere.
+    // Super is the Cmd key on macOS and a separate key elsewhere
+    fn normalize_platform_accelerator(accelerator: &str, is_macos: bool) -> String {
+        let mut modifiers: Vec<&str> = Vec::new();
+        let mut key = String::new();
+
+        for part in accelerator.split('+').map(|part| part.trim()) {
+            match part.to_lowercase().as_str() {
+                "cmdorctrl" | "commandorcontrol" => modifiers.push("CmdOrCtrl"),
+                "ctrl" | "control" if !is_macos => modifiers.push("CmdOrCtrl"),
+                "ctrl" | "control" => modifiers.push("Ctrl"),
+                "cmd" | "command" | "super" | "meta" if is_macos => modifiers.push("CmdOrCtrl"),
+                "cmd" | "command" | "super" | "meta" => modifiers.push("Super"),
+                "alt" | "option" => modifiers.push("Alt"),
+                "shift" => modifiers.push("Shift"),
+                _ => key = part.to_uppercase(),
+            }
+        }
+
+        modifiers.sort_unstable();
+        modifiers.dedup();
+        modifiers.push(&key);
+
+        modifiers.join("+")
+    }
+
+    // Finds hotkeys assigned to more than one action
+    pub fn find_duplicate_hotkeys(hotkeys: &HotkeySettings) -> Vec<HotkeyConflict> {
+        let bindings = get_hotkey_bindings(hotkeys);
+
+        bindings
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (action, accelerator))| {
+                let normalized = Self::normalize_accelerator(accelerator);
+
+                bindings
+                    .iter()
+                    .enumerate()
+                    .find(|(other_index, (_, other_accelerator))| {
+                        *other_index != index
+                            && Self::normalize_accelerator(other_accelerator) == normalized
+                    })
+                    .map(|(_, (other_action, _))| {
+                        create_conflict(
+                            *action,
+                            accelerator,
+                            &format!("Also assigned to '{}'", other_action.get_name()),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    // Checks hotkeys for duplicates and for shortcuts taken by other applications
+    pub fn validate_hotkeys(
+        app_handle: &AppHandle,
+        hotkeys: &HotkeySettings,
+    ) -> Vec<HotkeyConflict> {
+        let mut conflicts = Self::find_duplicate_hotkeys(hotkeys);
+        let mut shortcut_manager = app_handle.global_shortcut_manager();
+
+        for (action, accelerator) in get_hotkey_bindings(hotkeys) {
+            // Shortcuts registered by us are available by definition
+            if shortcut_manager.is_registered(accelerator).unwrap_or(false) {
+                continue;
+            }
+
+            match shortcut_manager.register(accelerator, || {}) {
+                Ok(_) => {
+                    let _ = shortcut_manager.unregister(accelerator);
+                }
+                Err(err) => {
+                    debug!("Hotkey '{}' is not available: {:?}", accelerator, err);
+
+                    conflicts.push(create_conflict(
+                        action,
+                        accelerator,
+                        "Invalid or used by another application",
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    // Replaces all registered global shortcuts with the ones from settings
+    pub fn register_hotkeys(
+        app_handle: &AppHandle,
+        hotkeys: &HotkeySettings,
+    ) -> Vec<HotkeyConflict> {
+        let mut shortcut_manager = app_handle.global_shortcut_manager();
+
+        if let Err(err) = shortcut_manager.unregister_all() {
+            error!("Failed to unregister hotkeys: {:?}", err);
+        }
+
+        let mut conflicts = Self::find_duplicate_hotkeys(hotkeys);
+
+        for (action, accelerator) in get_hotkey_bindings(hotkeys) {
+            if shortcut_manager.is_registered(accelerator).unwrap_or(false) {
+                continue;
+            }
+
+            let app_handle = app_handle.clone();
+
+            match shortcut_manager.register(accelerator, move || {
+                info!("Hotkey triggered: {:?}", action);
+                action.run(&app_handle);
+            }) {
+                Ok(_) => info!("Registered hotkey {} for {:?}", accelerator, action),
+                Err(err) => {
+                    error!("Failed to register hotkey {}: {:?}", accelerator, err);
+
+                    conflicts.push(create_conflict(
+                        action,
+                        accelerator,
+                        "Invalid or used by another application",
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_accelerator() {
+        assert_eq!(
+            HotkeysService::normalize_platform_accelerator("shift+ctrl+l", false),
+            "CmdOrCtrl+Shift+L"
+        );
+        assert_eq!(
+            HotkeysService::normalize_accelerator("CmdOrCtrl + Shift + L"),
+            "CmdOrCtrl+Shift+L"
+        );
+        assert_eq!(HotkeysService::normalize_accelerator("Option+F1"), "Alt+F1");
+    }
+
+    #[test]
+    fn test_normalize_accelerator_super() {
+        let normalize = HotkeysService::normalize_platform_accelerator;
+
+        assert_eq!(normalize("Super+L", false), "Super+L");
+        assert_eq!(normalize("meta+l", false), "Super+L");
+        assert_eq!(normalize("Cmd+L", false), "Super+L");
+        assert_ne!(normalize("Super+L", false), normalize("Ctrl+L", false));
+
+        assert_eq!(normalize("Cmd+L", true), "CmdOrCtrl+L");
+        assert_eq!(normalize("Ctrl+L", true), "Ctrl+L");
+        assert_eq!(normalize("Super+L", true), "CmdOrCtrl+L");
+        assert_eq!(normalize("meta+l", true), normalize("Cmd+L", true));
+        assert_ne!(normalize("Super+L", true), normalize("Ctrl+L", true));
+    }
+
+    #[test]
+    fn test_find_duplicate_hotkeys() {
+        let hotkeys = HotkeySettings {
+            toggle_lock: "CmdOrCtrl+Alt+L".to_string(),
+            clear_chat: "alt+cmdorctrl+l".to_string(),
+            ..HotkeySettings::default()
+        };
+
+        let conflicts = HotkeysService::find_duplicate_hotkeys(&hotkeys);
+
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].action, "Lock / Unlock");
+        assert_eq!(conflicts[0].reason, "Also assigned to 'Clear Chat'");
+        assert_eq!(conflicts[1].action, "Clear Chat");
+    }
+
+    #[test]
+    fn test_find_duplicate_hotkeys_ignores_disabled() {
+        let hotkeys = HotkeySettings {
+            toggle_lock: "".to_string(),
+            clear_chat: "".to_string(),
+            ..HotkeySettings::default()
+        };
+
+        assert!(HotkeysService::find_duplicate_hotkeys(&hotkeys).is_empty());
+    }
+}
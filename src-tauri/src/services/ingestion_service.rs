@@ -0,0 +1,225 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use gigachat_models::{AppSettings, RateLimitStrategy};
+use twitch_irc::message::PrivmsgMessage;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+const PRIVILEGED_BADGES: [&str; 5] = ["broadcaster", "moderator", "vip", "subscriber", "founder"];
+
+// Keeps track of events which happened during the last second
+#[derive(Debug, Default)]
+struct RateWindow {
+    events: VecDeque<Instant>,
+}
+
+impl RateWindow {
+    fn add(&mut self, now: Instant) {
+        self.events.push_back(now);
+    }
+
+    fn count(&mut self, now: Instant) -> usize {
+        while let Some(event) = self.events.front() {
+            if now.duration_since(*event) < RATE_WINDOW {
+                break;
+            }
+
+            self.events.pop_front();
+        }
+
+        self.events.len()
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimiter {
+    incoming: RateWindow,
+    accepted: RateWindow,
+    // Accumulates fractions of a message for sampling, message is shown once it reaches 1
+    sample_credit: f64,
+}
+
+impl RateLimiter {
+    fn accept(&mut self, limit: u32, strategy: RateLimitStrategy, now: Instant) -> bool {
+        if limit == 0 {
+            return true;
+        }
+
+        self.incoming.add(now);
+
+        let under_limit = self.accepted.count(now) < limit as usize;
+        let accepted = match strategy {
+            RateLimitStrategy::Drop => under_limit,
+            RateLimitStrategy::Sample => {
+                let incoming_rate = self.incoming.count(now) as f64;
+                self.sample_credit = (self.sample_credit + limit as f64 / incoming_rate).min(1_f64);
+
+                if self.sample_credit >= 1_f64 && under_limit {
+                    self.sample_credit -= 1_f64;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+
+        if accepted {
+            self.accepted.add(now);
+        }
+
+        accepted
+    }
+}
+
+fn is_privileged(msg: &PrivmsgMessage) -> bool {
+    msg.badges
+        .iter()
+        .any(|badge| PRIVILEGED_BADGES.contains(&badge.name.as_str()))
+}
+
+// Decides which chat messages are shown, so that busy channels stay readable
+#[derive(Debug, Default)]
+pub struct IngestionService {
+    rate_limiter: RateLimiter,
+}
+
+impl IngestionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn accept(&mut self, msg: &PrivmsgMessage, settings: &AppSettings) -> bool {
+        if settings.privileged_only && !is_privileged(msg) {
+            return false;
+        }
+
+        if msg.message_text.trim().chars().count() < settings.min_message_length as usize {
+            return false;
+        }
+
+        self.rate_limiter.accept(
+            settings.max_messages_per_second,
+            settings.rate_limit_strategy,
+            Instant::now(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils;
+
+    fn create_privmsg(badges: &str, text: &str) -> PrivmsgMessage {
+        test_utils::create_privmsg(&format!("badges={}", badges), text)
+    }
+
+    fn count_accepted(limiter: &mut RateLimiter, strategy: RateLimitStrategy) -> usize {
+        let started_at = Instant::now();
+
+        // 100 messages per second for 2 seconds
+        (0..200)
+            .filter(|i| limiter.accept(10, strategy, started_at + Duration::from_millis(i * 10)))
+            .count()
+    }
+
+    #[test]
+    fn test_rate_limiter_drop() {
+        let mut limiter = RateLimiter::default();
+        let started_at = Instant::now();
+
+        let accepted: Vec<bool> = (0..12)
+            .map(|i| {
+                limiter.accept(
+                    10,
+                    RateLimitStrategy::Drop,
+                    started_at + Duration::from_millis(i),
+                )
+            })
+            .collect();
+
+        assert!(accepted[..10].iter().all(|accepted| *accepted));
+        assert!(!accepted[10] && !accepted[11]);
+        assert!(limiter.accept(10, RateLimitStrategy::Drop, started_at + RATE_WINDOW));
+    }
+
+    #[test]
+    fn test_rate_limiter_keeps_limit() {
+        let mut drop_limiter = RateLimiter::default();
+        let mut sample_limiter = RateLimiter::default();
+
+        assert_eq!(
+            count_accepted(&mut drop_limiter, RateLimitStrategy::Drop),
+            20
+        );
+        assert!(count_accepted(&mut sample_limiter, RateLimitStrategy::Sample) <= 20);
+    }
+
+    #[test]
+    fn test_rate_limiter_sample_spreads_messages() {
+        let mut limiter = RateLimiter::default();
+        let started_at = Instant::now();
+
+        // Skip first second, while incoming rate is being measured
+        for i in 0..100 {
+            limiter.accept(
+                10,
+                RateLimitStrategy::Sample,
+                started_at + Duration::from_millis(i * 10),
+            );
+        }
+
+        let accepted: Vec<u64> = (100..200)
+            .filter(|i| {
+                limiter.accept(
+                    10,
+                    RateLimitStrategy::Sample,
+                    started_at + Duration::from_millis(i * 10),
+                )
+            })
+            .collect();
+
+        assert!(accepted.len() >= 9);
+        assert!(accepted.windows(2).all(|pair| pair[1] - pair[0] >= 5));
+    }
+
+    #[test]
+    fn test_rate_limiter_unlimited() {
+        let mut limiter = RateLimiter::default();
+        let now = Instant::now();
+
+        assert!((0..1000).all(|_| limiter.accept(0, RateLimitStrategy::Drop, now)));
+    }
+
+    #[test]
+    fn test_privileged_only() {
+        let mut ingestion_service = IngestionService::new();
+        let settings = AppSettings {
+            privileged_only: true,
+            ..AppSettings::default()
+        };
+
+        assert!(ingestion_service.accept(&create_privmsg("vip/1", "hello"), &settings));
+        assert!(ingestion_service.accept(
+            &create_privmsg("subscriber/12,premium/1", "hello"),
+            &settings
+        ));
+        assert!(!ingestion_service.accept(&create_privmsg("premium/1", "hello"), &settings));
+        assert!(!ingestion_service.accept(&create_privmsg("", "hello"), &settings));
+    }
+
+    #[test]
+    fn test_min_message_length() {
+        let mut ingestion_service = IngestionService::new();
+        let settings = AppSettings {
+            min_message_length: 5,
+            ..AppSettings::default()
+        };
+
+        assert!(ingestion_service.accept(&create_privmsg("", "hello"), &settings));
+        assert!(!ingestion_service.accept(&create_privmsg("", "  hi  "), &settings));
+    }
+}
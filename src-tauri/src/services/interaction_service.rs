@@ -0,0 +1,213 @@
+use std::{sync::Mutex, thread, time::Duration};
+
+use device_query::{DeviceQuery, DeviceState, Keycode};
+use gigachat_models::{AppSettings, InteractModifier, UpdateInteractionEventArgs};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    services::MAIN_WINDOW_LABEL, state::ui_lock_state::UiLockState, utils::emit_toggle_interaction,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Size of the top-right corner area which enables interaction on hover
+const HOTSPOT_SIZE: f64 = 24_f64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WindowRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl WindowRect {
+    fn contains(&self, point: (i32, i32)) -> bool {
+        let (x, y) = (point.0 as f64, point.1 as f64);
+
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    fn get_hotspot(&self, scale_factor: f64) -> WindowRect {
+        let size = HOTSPOT_SIZE * scale_factor;
+
+        WindowRect {
+            x: self.x + self.width - size,
+            y: self.y,
+            width: size,
+            height: size,
+        }
+    }
+}
+
+fn is_modifier_pressed(modifier: InteractModifier, keys: &[Keycode]) -> bool {
+    let modifier_keys: &[Keycode] = match modifier {
+        InteractModifier::None => &[],
+        InteractModifier::Ctrl => &[Keycode::LControl, Keycode::RControl],
+        InteractModifier::Alt => &[Keycode::LAlt, Keycode::RAlt],
+        InteractModifier::Shift => &[Keycode::LShift, Keycode::RShift],
+    };
+
+    keys.iter().any(|key| modifier_keys.contains(key))
+}
+
+// Hotspot activates interaction, which then lasts while cursor stays in the window
+fn is_hotspot_active(in_hotspot: bool, in_window: bool, was_hotspot_active: bool) -> bool {
+    in_hotspot || (was_hotspot_active && in_window)
+}
+
+// Decides if locked overlay should accept mouse events.
+// Modifier keeps interaction only while it is held
+fn should_be_interactive(modifier_pressed: bool, hotspot_active: bool) -> bool {
+    modifier_pressed || hotspot_active
+}
+
+pub struct InteractionService {}
+
+impl InteractionService {
+    // Makes locked overlay temporarily interactive while modifier key is held
+    // or after cursor enters the hotspot
+    pub fn watch(app_handle: AppHandle) {
+        thread::spawn(move || {
+            let device_state = DeviceState::new();
+            let mut is_interactive = false;
+            let mut hotspot_active = false;
+
+            loop {
+                thread::sleep(POLL_INTERVAL);
+
+                let is_ui_locked = *app_handle.state::<UiLockState>().lock().unwrap();
+                if !is_ui_locked {
+                    // Unlocked overlay handles cursor events by itself
+                    hotspot_active = false;
+
+                    if is_interactive {
+                        is_interactive = false;
+                        emit_toggle_interaction(
+                            &app_handle,
+                            UpdateInteractionEventArgs { interactive: false },
+                        );
+                    }
+
+                    continue;
+                }
+
+                let (modifier, hotspot_enabled) = {
+                    let settings_state = app_handle.state::<Mutex<AppSettings>>();
+                    let settings = settings_state.lock().unwrap();
+
+                    (settings.interact_modifier, settings.interact_hotspot)
+                };
+
+                if modifier == InteractModifier::None && !hotspot_enabled && !is_interactive {
+                    continue;
+                }
+
+                let main_window = match app_handle.get_window(MAIN_WINDOW_LABEL) {
+                    Some(window) => window,
+                    None => continue,
+                };
+
+                let (position, size, scale_factor) = match (
+                    main_window.outer_position(),
+                    main_window.outer_size(),
+                    main_window.scale_factor(),
+                ) {
+                    (Ok(position), Ok(size), Ok(scale_factor)) => (position, size, scale_factor),
+                    _ => continue,
+                };
+
+                let window_rect = WindowRect {
+                    x: position.x as f64,
+                    y: position.y as f64,
+                    width: size.width as f64,
+                    height: size.height as f64,
+                };
+                let cursor = device_state.get_mouse().coords;
+
+                let modifier_pressed = is_modifier_pressed(modifier, &device_state.get_keys());
+                let in_hotspot =
+                    hotspot_enabled && window_rect.get_hotspot(scale_factor).contains(cursor);
+                let in_window = window_rect.contains(cursor);
+
+                hotspot_active = is_hotspot_active(in_hotspot, in_window, hotspot_active);
+
+                let new_is_interactive = should_be_interactive(modifier_pressed, hotspot_active);
+
+                if new_is_interactive == is_interactive {
+                    continue;
+                }
+
+                is_interactive = new_is_interactive;
+                debug!("Overlay interaction changed: {}", is_interactive);
+
+                let _ = main_window.set_ignore_cursor_events(!is_interactive);
+
+                emit_toggle_interaction(
+                    &app_handle,
+                    UpdateInteractionEventArgs {
+                        interactive: is_interactive,
+                    },
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_window_rect() -> WindowRect {
+        WindowRect {
+            x: 100_f64,
+            y: 100_f64,
+            width: 400_f64,
+            height: 300_f64,
+        }
+    }
+
+    #[test]
+    fn test_window_rect_hotspot() {
+        let hotspot = get_window_rect().get_hotspot(2_f64);
+
+        assert!(hotspot.contains((499, 100)));
+        assert!(hotspot.contains((452, 147)));
+        assert!(!hotspot.contains((451, 100)));
+        assert!(!hotspot.contains((499, 148)));
+    }
+
+    #[test]
+    fn test_is_hotspot_active() {
+        assert!(is_hotspot_active(true, true, false));
+        assert!(!is_hotspot_active(false, true, false));
+        assert!(is_hotspot_active(false, true, true));
+        assert!(!is_hotspot_active(false, false, true));
+    }
+
+    #[test]
+    fn test_should_be_interactive() {
+        assert!(should_be_interactive(true, false));
+        assert!(should_be_interactive(false, true));
+        assert!(!should_be_interactive(false, false));
+    }
+
+    #[test]
+    fn test_modifier_release_in_window() {
+        // Modifier held inside the window, then released without visiting the hotspot
+        let hotspot_active = is_hotspot_active(false, true, false);
+        assert!(should_be_interactive(true, hotspot_active));
+
+        let hotspot_active = is_hotspot_active(false, true, hotspot_active);
+        assert!(!should_be_interactive(false, hotspot_active));
+    }
+
+    #[test]
+    fn test_is_modifier_pressed() {
+        let keys = vec![Keycode::A, Keycode::RAlt];
+
+        assert!(is_modifier_pressed(InteractModifier::Alt, &keys));
+        assert!(!is_modifier_pressed(InteractModifier::Ctrl, &keys));
+        assert!(!is_modifier_pressed(InteractModifier::None, &keys));
+    }
+}
@@ -0,0 +1,596 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use futures::{SinkExt, StreamExt};
+use gigachat_models::{AppSettings, GigaChatMessage};
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::{
+    emote_providers::{EmoteUpdate, LiveEmoteProvider, EMOTE_PROVIDERS},
+    services::{EmoteRenderOptions, EmotesService, OverlayWindowsService},
+    state::saved_emotes_state::{EmoteUrls, EmotesMap, EmotesState},
+    utils::emit_new_messages,
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+struct ChannelSubscription {
+    channel_id: String,
+    provider_ids: Vec<&'static str>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ChannelSubscription {
+    fn stop(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+// Patches channel emotes, returns code of the changed emote before the update.
+// Emote of another provider hidden by a removed one comes back after emotes are fetched again
+fn apply_emote_update(
+    channel_emotes: &mut EmotesMap,
+    provider_id: &'static str,
+    update: &EmoteUpdate,
+    get_priority: impl Fn(&str) -> i32,
+) -> Option<String> {
+    let find_code = |emote_id: &str| {
+        channel_emotes
+            .iter()
+            .find(|(_, emote)| emote.provider_id == provider_id && emote.emote_id == emote_id)
+            .map(|(code, _)| code.clone())
+    };
+
+    // Emotes of higher priority providers keep their codes, as with fetched emotes
+    let is_overridden = |channel_emotes: &EmotesMap, code: &str| {
+        channel_emotes.get(code).map_or(false, |existing| {
+            get_priority(existing.provider_id) > get_priority(provider_id)
+        })
+    };
+
+    match update {
+        EmoteUpdate::Added { code, emote, .. } => {
+            if is_overridden(channel_emotes, code) {
+                return None;
+            }
+
+            let emote = EmoteUrls {
+                provider_id,
+                ..emote.clone()
+            };
+            channel_emotes.insert(code.clone(), emote);
+
+            Some(code.clone())
+        }
+        EmoteUpdate::Removed { emote_id, .. } => {
+            let code = find_code(emote_id)?;
+            channel_emotes.remove(&code);
+
+            Some(code)
+        }
+        EmoteUpdate::Renamed { emote_id, code, .. } => {
+            let old_code = find_code(emote_id)?;
+            let emote = channel_emotes.remove(&old_code)?;
+
+            if !is_overridden(channel_emotes, code) {
+                channel_emotes.insert(code.clone(), emote);
+            }
+
+            Some(old_code)
+        }
+    }
+}
+
+fn get_update_text(update: &EmoteUpdate, old_code: &str) -> String {
+    let (text, actor) = match update {
+        EmoteUpdate::Added { code, actor, .. } => (format!("Emote {} added", code), actor),
+        EmoteUpdate::Removed { actor, .. } => (format!("Emote {} removed", old_code), actor),
+        EmoteUpdate::Renamed { code, actor, .. } => {
+            (format!("Emote {} renamed to {}", old_code, code), actor)
+        }
+    };
+
+    match actor {
+        Some(actor) => format!("{} by {}", text, actor),
+        None => text,
+    }
+}
+
+// Connects to the provider event stream and reports emote changes until the connection closes
+async fn listen_events(
+    url: &str,
+    provider: &dyn LiveEmoteProvider,
+    twitch_user_id: &str,
+    on_update: &impl Fn(EmoteUpdate),
+) -> anyhow::Result<()> {
+    let subscribe_messages = provider.get_subscribe_messages(twitch_user_id).await?;
+    let (mut socket, _) = connect_async(url).await?;
+
+    for message in subscribe_messages {
+        socket.send(Message::Text(message)).await?;
+    }
+
+    while let Some(message) = socket.next().await {
+        if let Message::Text(text) = message? {
+            for update in provider.parse_event(&text, twitch_user_id) {
+                on_update(update);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Subscribes to live emote changes of joined channels and announces them in chat
+pub struct LiveEmotesService {
+    // Replaced endpoints by provider id, e.g. a local WebSocket server for testing
+    events_urls: HashMap<String, String>,
+    subscriptions: Mutex<HashMap<String, ChannelSubscription>>,
+}
+
+impl LiveEmotesService {
+    pub fn new(events_urls: HashMap<String, String>) -> Self {
+        Self {
+            events_urls,
+            subscriptions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Reads `GIGACHAT_<PROVIDER ID>_EVENTS_URL` variables, e.g. `GIGACHAT_SEVENTV_EVENTS_URL`
+    pub fn from_env() -> Self {
+        let events_urls = EMOTE_PROVIDERS
+            .get_enabled_providers(&[], |capabilities| capabilities.live_updates)
+            .into_iter()
+            .filter_map(|provider| {
+                let variable = format!("GIGACHAT_{}_EVENTS_URL", provider.get_id().to_uppercase());
+                let url = std::env::var(variable).ok()?;
+
+                Some((provider.get_id().to_string(), url))
+            })
+            .collect();
+
+        Self::new(events_urls)
+    }
+
+    // Starts listening to providers with live updates, unless the channel already listens to them.
+    // Called whenever channel emotes are fetched, so that changed providers are applied
+    pub fn subscribe(
+        &self,
+        app_handle: &AppHandle,
+        channel_login: &str,
+        channel_id: &str,
+        disabled_providers: &[String],
+    ) {
+        let is_enabled = OverlayWindowsService::get_channel_windows(app_handle, channel_login)
+            .iter()
+            .any(|(_, settings)| settings.live_emote_updates);
+        let providers: Vec<_> = if is_enabled {
+            EMOTE_PROVIDERS
+                .get_enabled_providers(disabled_providers, |capabilities| capabilities.live_updates)
+                .into_iter()
+                .filter_map(|provider| Some((provider.get_id(), provider.get_live_updates()?)))
+                .collect()
+        } else {
+            vec![]
+        };
+        let provider_ids: Vec<&'static str> = providers.iter().map(|(id, _)| *id).collect();
+
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+
+        if let Some(subscription) = subscriptions.get(channel_login) {
+            if subscription.channel_id == channel_id && subscription.provider_ids == provider_ids {
+                return;
+            }
+
+            subscription.stop();
+        }
+
+        if providers.is_empty() {
+            subscriptions.remove(channel_login);
+            return;
+        }
+
+        let tasks = providers
+            .into_iter()
+            .map(|(provider_id, provider)| {
+                let url = self
+                    .events_urls
+                    .get(provider_id)
+                    .cloned()
+                    .unwrap_or_else(|| provider.get_events_url().to_string());
+                let app_handle = app_handle.clone();
+                let channel_login = channel_login.to_string();
+                let channel_id = channel_id.to_string();
+
+                tauri::async_runtime::spawn(async move {
+                    let on_update = |update| {
+                        Self::apply_update(&app_handle, &channel_login, provider_id, update)
+                    };
+
+                    loop {
+                        info!(
+                            "Listening to {} emote updates of {}",
+                            provider_id, channel_login
+                        );
+
+                        if let Err(err) =
+                            listen_events(&url, provider, &channel_id, &on_update).await
+                        {
+                            warn!("{} emote updates failed: {:?}", provider_id, err);
+                        }
+
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                })
+            })
+            .collect();
+
+        subscriptions.insert(
+            channel_login.to_string(),
+            ChannelSubscription {
+                channel_id: channel_id.to_string(),
+                provider_ids,
+                tasks,
+            },
+        );
+    }
+
+    // Applies changed settings to all joined channels
+    pub fn update_subscriptions(&self, app_handle: &AppHandle) {
+        let channel_ids = app_handle
+            .state::<Mutex<EmotesState>>()
+            .lock()
+            .unwrap()
+            .channel_ids
+            .clone();
+        let disabled_providers = app_handle
+            .state::<Mutex<AppSettings>>()
+            .lock()
+            .unwrap()
+            .disabled_emote_providers
+            .clone();
+
+        for (channel_login, channel_id) in channel_ids {
+            self.subscribe(app_handle, &channel_login, &channel_id, &disabled_providers);
+        }
+    }
+
+    pub fn unsubscribe(&self, channel_login: &str) {
+        if let Some(subscription) = self.subscriptions.lock().unwrap().remove(channel_login) {
+            subscription.stop();
+        }
+    }
+
+    fn apply_update(
+        app_handle: &AppHandle,
+        channel_login: &str,
+        provider_id: &'static str,
+        update: EmoteUpdate,
+    ) {
+        let (old_code, emote) = {
+            let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+            let mut emotes_state = emotes_state.lock().unwrap();
+
+            let channel_emotes = match emotes_state.channels_emotes.get_mut(channel_login) {
+                Some(channel_emotes) => channel_emotes,
+                // Channel emotes are being fetched again, they include the change already
+                None => return,
+            };
+            let old_code = match apply_emote_update(channel_emotes, provider_id, &update, |id| {
+                EMOTE_PROVIDERS.get_priority(id)
+            }) {
+                Some(old_code) => old_code,
+                None => return,
+            };
+
+            let emote = match &update {
+                EmoteUpdate::Added { code, .. } | EmoteUpdate::Renamed { code, .. } => {
+                    channel_emotes
+                        .get(code)
+                        .map(|emote| (code.clone(), emote.clone()))
+                }
+                EmoteUpdate::Removed { .. } => None,
+            };
+
+            (old_code, emote)
+        };
+
+        let text = get_update_text(&update, &old_code);
+        info!("{} in {}", text, channel_login);
+
+        // Announcements skip batching and filters, they are rare and come from the app itself
+        for (window_label, settings) in
+            OverlayWindowsService::get_channel_windows(app_handle, channel_login)
+        {
+            if !settings.live_emote_updates {
+                continue;
+            }
+
+            let text_html = format!(
+                "<span class=\"gc-text\">{}</span>",
+                html_escape::encode_text(&text)
+            );
+            let message_html = match &emote {
+                Some((code, emote)) => format!(
+                    "{}{}",
+                    EmotesService::get_emote_html(
+                        emote,
+                        code,
+                        &EmoteRenderOptions::from_settings(&settings)
+                    ),
+                    text_html
+                ),
+                None => text_html,
+            };
+            let message = GigaChatMessage::new_system(
+                text.clone(),
+                message_html,
+                format!("live-emote-{}", rand::random::<u64>()),
+            );
+
+            emit_new_messages(vec![message], &window_label, app_handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::emote_providers::{
+        better_ttv_emote_provider::BetterTtvEmoteProvider,
+        seven_tv_emote_provider::SevenTvEmoteProvider,
+    };
+
+    fn create_emote(provider_id: &'static str, emote_id: &str) -> EmoteUrls {
+        EmoteUrls {
+            provider_id,
+            emote_id: emote_id.to_string(),
+            ..EmoteUrls::new(BTreeMap::from([(1, format!("_{}_url_", emote_id))]), None)
+        }
+    }
+
+    fn get_priority(provider_id: &str) -> i32 {
+        match provider_id {
+            "seventv" => 30,
+            "betterttv" => 20,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_emote_update() {
+        let mut channel_emotes = EmotesMap::from([
+            ("Kappa".to_string(), create_emote("seventv", "1")),
+            ("Pog".to_string(), create_emote("betterttv", "2")),
+        ]);
+
+        let added = EmoteUpdate::Added {
+            code: "KEKW".to_string(),
+            emote: EmoteUrls::new(BTreeMap::new(), None),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "betterttv", &added, get_priority),
+            Some("KEKW".to_string())
+        );
+        assert_eq!(channel_emotes["KEKW"].provider_id, "betterttv");
+
+        // Lower priority provider doesn't replace an emote with the same code
+        let added = EmoteUpdate::Added {
+            code: "Kappa".to_string(),
+            emote: create_emote("", "3"),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "betterttv", &added, get_priority),
+            None
+        );
+        assert_eq!(channel_emotes["Kappa"].emote_id, "1");
+
+        let renamed = EmoteUpdate::Renamed {
+            emote_id: "2".to_string(),
+            code: "PogU".to_string(),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "betterttv", &renamed, get_priority),
+            Some("Pog".to_string())
+        );
+        assert_eq!(channel_emotes["PogU"].emote_id, "2");
+
+        // Ids are matched within the provider only
+        let removed = EmoteUpdate::Removed {
+            emote_id: "1".to_string(),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "betterttv", &removed, get_priority),
+            None
+        );
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "seventv", &removed, get_priority),
+            Some("Kappa".to_string())
+        );
+
+        let mut codes: Vec<&String> = channel_emotes.keys().collect();
+        codes.sort();
+        assert_eq!(codes, vec!["KEKW", "PogU"]);
+    }
+
+    #[test]
+    fn test_apply_emote_update_rename_priority() {
+        let mut channel_emotes = EmotesMap::from([
+            ("Kappa".to_string(), create_emote("seventv", "1")),
+            ("Pog".to_string(), create_emote("betterttv", "2")),
+        ]);
+
+        // Lower priority provider doesn't take over a code by renaming either
+        let renamed = EmoteUpdate::Renamed {
+            emote_id: "2".to_string(),
+            code: "Kappa".to_string(),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "betterttv", &renamed, get_priority),
+            Some("Pog".to_string())
+        );
+        assert_eq!(channel_emotes["Kappa"].provider_id, "seventv");
+        assert_eq!(channel_emotes["Kappa"].emote_id, "1");
+        assert!(!channel_emotes.contains_key("Pog"));
+
+        // Higher priority provider replaces the code
+        channel_emotes.insert("Pog".to_string(), create_emote("betterttv", "2"));
+        let renamed = EmoteUpdate::Renamed {
+            emote_id: "1".to_string(),
+            code: "Pog".to_string(),
+            actor: None,
+        };
+        assert_eq!(
+            apply_emote_update(&mut channel_emotes, "seventv", &renamed, get_priority),
+            Some("Kappa".to_string())
+        );
+        assert_eq!(channel_emotes["Pog"].provider_id, "seventv");
+        assert!(!channel_emotes.contains_key("Kappa"));
+    }
+
+    #[test]
+    fn test_get_update_text() {
+        let added = EmoteUpdate::Added {
+            code: "KEKW".to_string(),
+            emote: EmoteUrls::new(BTreeMap::new(), None),
+            actor: Some("Streamer".to_string()),
+        };
+        let renamed = EmoteUpdate::Renamed {
+            emote_id: "1".to_string(),
+            code: "PogU".to_string(),
+            actor: None,
+        };
+
+        assert_eq!(
+            get_update_text(&added, "KEKW"),
+            "Emote KEKW added by Streamer"
+        );
+        assert_eq!(
+            get_update_text(&renamed, "Pog"),
+            "Emote Pog renamed to PogU"
+        );
+    }
+
+    #[test]
+    fn test_parse_seventv_event() {
+        let event = r#"{
+            "op": 0,
+            "d": {
+                "type": "emote_set.update",
+                "body": {
+                    "id": "set",
+                    "actor": { "id": "user", "display_name": "Streamer" },
+                    "pushed": [{
+                        "key": "emotes",
+                        "index": 10,
+                        "value": {
+                            "id": "new",
+                            "name": "KEKW",
+                            "data": {
+                                "id": "new",
+                                "name": "KEKW",
+                                "animated": false,
+                                "host": {
+                                    "url": "//cdn.7tv.app/emote/new",
+                                    "files": [{
+                                        "name": "1x.webp",
+                                        "static_name": "1x_static.webp",
+                                        "width": 32,
+                                        "height": 32,
+                                        "format": "WEBP"
+                                    }]
+                                }
+                            }
+                        }
+                    }],
+                    "pulled": [{
+                        "key": "emotes",
+                        "index": 3,
+                        "old_value": { "id": "old", "name": "Pog" }
+                    }]
+                }
+            }
+        }"#;
+
+        let updates = SevenTvEmoteProvider::default().parse_event(event, "1");
+
+        assert_eq!(
+            updates,
+            vec![
+                EmoteUpdate::Added {
+                    code: "KEKW".to_string(),
+                    emote: EmoteUrls {
+                        emote_id: "new".to_string(),
+                        ..EmoteUrls::new(
+                            BTreeMap::from([(
+                                1,
+                                "https://cdn.7tv.app/emote/new/1x.webp".to_string()
+                            )]),
+                            None
+                        )
+                    },
+                    actor: Some("Streamer".to_string()),
+                },
+                EmoteUpdate::Removed {
+                    emote_id: "old".to_string(),
+                    actor: Some("Streamer".to_string()),
+                },
+            ]
+        );
+        assert!(SevenTvEmoteProvider::default()
+            .parse_event(r#"{"op":2,"d":{"count":1}}"#, "1")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_listen_events_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let join_message = socket.next().await.unwrap().unwrap();
+
+            for event in [
+                r#"{"name":"emote_delete","data":{"channel":"twitch:2","emoteId":"other"}}"#,
+                r#"{"name":"emote_delete","data":{"channel":"twitch:1","emoteId":"abc"}}"#,
+            ] {
+                socket.send(Message::Text(event.to_string())).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+
+            join_message
+        });
+
+        let updates = RefCell::new(Vec::new());
+        listen_events(&url, &BetterTtvEmoteProvider::default(), "1", &|update| {
+            updates.borrow_mut().push(update)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            server.await.unwrap(),
+            Message::Text(r#"{"name":"join_channel","data":{"name":"twitch:1"}}"#.to_string())
+        );
+        assert_eq!(
+            updates.into_inner(),
+            vec![EmoteUpdate::Removed {
+                emote_id: "abc".to_string(),
+                actor: None,
+            }]
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use gigachat_models::{AppSettings, GigaChatMessage, GigaChatSender, UpdateMessageEventArgs};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use twitch_irc::message::{IRCMessage, PrivmsgMessage, ServerMessage};
+
+use crate::{
+    services::{DedupService, EmoteRenderOptions, EmotesService, StatsService, MAIN_WINDOW_LABEL},
+    state::saved_emotes_state::{get_saved_emotes_state, EmotesState},
+    utils::{emit_message_updated, emit_new_messages},
+};
+
+// Messages received within this interval are delivered to the UI as a single event
+const BATCH_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Debug, Clone)]
+pub enum ChatEvent {
+    NewMessage(GigaChatMessage),
+    MessageUpdated(UpdateMessageEventArgs),
+}
+
+// Chat event for a single overlay window
+#[derive(Debug, Clone)]
+pub struct WindowChatEvent {
+    pub window_label: String,
+    pub event: ChatEvent,
+}
+
+impl WindowChatEvent {
+    pub fn new(window_label: &str, event: ChatEvent) -> Self {
+        Self {
+            window_label: window_label.to_string(),
+            event,
+        }
+    }
+}
+
+pub type MessagesSender = UnboundedSender<WindowChatEvent>;
+
+// Waits for the first message and then collects everything that arrives during the batch interval
+async fn collect_batch<T>(
+    receiver: &mut UnboundedReceiver<T>,
+    interval: Duration,
+) -> Option<Vec<T>> {
+    let first = receiver.recv().await?;
+    tokio::time::sleep(interval).await;
+
+    let mut batch = vec![first];
+    while let Ok(item) = receiver.try_recv() {
+        batch.push(item);
+    }
+
+    Some(batch)
+}
+
+// Splits batch by target window, keeping order of windows and events
+fn group_by_window(batch: Vec<WindowChatEvent>) -> Vec<(String, Vec<ChatEvent>)> {
+    let mut groups: Vec<(String, Vec<ChatEvent>)> = Vec::new();
+    let mut group_indexes: HashMap<String, usize> = HashMap::new();
+
+    for WindowChatEvent {
+        window_label,
+        event,
+    } in batch
+    {
+        match group_indexes.get(&window_label) {
+            Some(index) => groups[*index].1.push(event),
+            None => {
+                group_indexes.insert(window_label.clone(), groups.len());
+                groups.push((window_label, vec![event]));
+            }
+        }
+    }
+
+    groups
+}
+
+// Splits batch into new messages and updates,
+// updates of messages from the same batch are applied before sending
+fn merge_batch(batch: Vec<ChatEvent>) -> (Vec<GigaChatMessage>, Vec<UpdateMessageEventArgs>) {
+    let mut new_messages: Vec<GigaChatMessage> = Vec::new();
+    let mut updates: Vec<UpdateMessageEventArgs> = Vec::new();
+
+    for event in batch {
+        match event {
+            ChatEvent::NewMessage(message) => new_messages.push(message),
+            ChatEvent::MessageUpdated(update) => {
+                match new_messages
+                    .iter_mut()
+                    .find(|message| message.message_id == update.message_id)
+                {
+                    Some(message) => message.repeat_count = update.repeat_count,
+                    None => updates.push(update),
+                }
+            }
+        }
+    }
+
+    (new_messages, updates)
+}
+
+// Recorded burst is a text file with `<offset ms> <raw irc line>` per line
+fn parse_recorded_burst(content: &str) -> Vec<(Duration, PrivmsgMessage)> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .filter_map(|line| {
+            let (offset, raw_message) = line.split_once(' ')?;
+            let offset = Duration::from_millis(offset.parse().ok()?);
+
+            let irc_message = IRCMessage::parse(raw_message).ok()?;
+            match ServerMessage::try_from(irc_message) {
+                Ok(ServerMessage::Privmsg(msg)) => Some((offset, msg)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+pub struct MessagesService {}
+
+impl MessagesService {
+    // Message html is built with settings of the window which shows it
+    pub fn create_message(
+        app_handle: &AppHandle,
+        msg: &PrivmsgMessage,
+        settings: &AppSettings,
+    ) -> GigaChatMessage {
+        let emotes = get_saved_emotes_state(app_handle, &msg.channel_login);
+        let render_options = EmoteRenderOptions::from_settings(settings);
+        let twitch_emotes = EmotesService::parse_twitch_message_emotes(msg);
+
+        // Positions of Twitch emotes don't matter for repeated emotes, so invisible characters
+        // can be stripped from the whole text here
+        let repeated_emote_text = if settings.strip_invisible_characters {
+            EmotesService::strip_invisible_characters(&msg.message_text)
+        } else {
+            msg.message_text.clone()
+        };
+
+        let repeated_emote = if settings.collapse_emote_spam {
+            EmotesService::get_repeated_emote(&emotes, &twitch_emotes, &repeated_emote_text)
+        } else {
+            None
+        };
+
+        let repeated_emote_urls = repeated_emote
+            .and_then(|(emote, _)| EmotesService::find_emote(&emotes, &twitch_emotes, emote));
+
+        let message_with_emotes = match (repeated_emote, repeated_emote_urls) {
+            (Some((emote, count)), Some(emote_urls)) => format!(
+                "{}<span class=\"gc-text gc-repeat-count\"> ×{}</span>",
+                EmotesService::get_emote_html(emote_urls, emote, &render_options),
+                count
+            ),
+            _ => EmotesService::replace_message_emotes(
+                &emotes,
+                &msg.message_text,
+                &msg.emotes,
+                &render_options,
+            ),
+        };
+
+        debug!("message_with_emotes {:?}", message_with_emotes);
+
+        GigaChatMessage {
+            sender: GigaChatSender::new(
+                msg.sender.login.clone(),
+                msg.sender.name.clone(),
+                msg.name_color.as_ref().map(|color| color.to_string()),
+            ),
+            message: msg.message_text.to_string(),
+            message_html: message_with_emotes,
+            message_id: msg.message_id.to_string(),
+            timestamp: msg.server_timestamp,
+            repeat_count: 1,
+            system: false,
+        }
+    }
+
+    // Counted once per chat message, before windows filter it out.
+    // Emotes are found the same way `create_message` renders them
+    pub fn record_stats(app_handle: &AppHandle, msg: &PrivmsgMessage) {
+        let strip_invisible_characters = app_handle
+            .state::<Mutex<AppSettings>>()
+            .lock()
+            .unwrap()
+            .strip_invisible_characters;
+        let message_emotes = {
+            let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+            let emotes_state = emotes_state.lock().unwrap();
+
+            EmotesService::get_message_emotes(
+                |code| emotes_state.get_emote(&msg.channel_login, code),
+                &msg.message_text,
+                &msg.emotes,
+                strip_invisible_characters,
+            )
+        };
+
+        app_handle.state::<StatsService>().record_message(
+            &msg.channel_login,
+            &msg.sender.login,
+            &msg.sender.name,
+            message_emotes,
+        );
+    }
+
+    pub fn get_dedup_key(app_handle: &AppHandle, channel_login: &str, message: &str) -> String {
+        let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+        let emotes = emotes_state.lock().unwrap();
+
+        DedupService::get_key(message, |word| emotes.contains_emote(channel_login, word))
+    }
+
+    // Checks whether message addresses the channel owner, e.g. `@Streamer hi`
+    pub fn is_mention(message_text: &str, channel_name: &str) -> bool {
+        let channel_name = channel_name.trim().to_lowercase();
+
+        !channel_name.is_empty()
+            && message_text.split_whitespace().any(|word| {
+                word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_')
+                    .to_lowercase()
+                    == channel_name
+            })
+    }
+
+    // Starts delivering chat messages to the UI in batches,
+    // so busy chats don't flood the webview with an event per message
+    pub fn start_batching(app_handle: AppHandle) -> MessagesSender {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<WindowChatEvent>();
+
+        tauri::async_runtime::spawn(async move {
+            while let Some(batch) = collect_batch(&mut receiver, BATCH_INTERVAL).await {
+                for (window_label, events) in group_by_window(batch) {
+                    let (new_messages, updates) = merge_batch(events);
+                    trace!(
+                        "Emitting batch of {} messages and {} updates to '{}'",
+                        new_messages.len(),
+                        updates.len(),
+                        window_label
+                    );
+
+                    if !new_messages.is_empty() {
+                        emit_new_messages(new_messages, &window_label, &app_handle);
+                    }
+
+                    for update in updates {
+                        emit_message_updated(update, &window_label, &app_handle);
+                    }
+                }
+            }
+        });
+
+        sender
+    }
+
+    // Replays recorded chat burst with original timings to benchmark the message pipeline
+    pub async fn replay_burst(
+        app_handle: &AppHandle,
+        path: &Path,
+        sender: &MessagesSender,
+    ) -> anyhow::Result<()> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let recorded_burst = parse_recorded_burst(&content);
+
+        info!(
+            "Replaying {} messages from {}",
+            recorded_burst.len(),
+            path.display()
+        );
+
+        let settings = app_handle
+            .state::<Mutex<AppSettings>>()
+            .lock()
+            .unwrap()
+            .clone();
+
+        let started_at = Instant::now();
+        let mut processing_time = Duration::ZERO;
+
+        for (offset, msg) in recorded_burst.iter() {
+            let elapsed = started_at.elapsed();
+            if *offset > elapsed {
+                tokio::time::sleep(*offset - elapsed).await;
+            }
+
+            let processing_started_at = Instant::now();
+            let chat_msg = Self::create_message(app_handle, msg, &settings);
+            let _ = sender.send(WindowChatEvent::new(
+                MAIN_WINDOW_LABEL,
+                ChatEvent::NewMessage(chat_msg),
+            ));
+            processing_time += processing_started_at.elapsed();
+        }
+
+        let elapsed = started_at.elapsed();
+        info!(
+            "Replayed {} messages in {:?} ({:.0} msg/s), processing took {:?}",
+            recorded_burst.len(),
+            elapsed,
+            recorded_burst.len() as f64 / elapsed.as_secs_f64(),
+            processing_time
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collect_batch() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+
+        for i in 0..5 {
+            sender.send(i).unwrap();
+        }
+
+        let batch = collect_batch(&mut receiver, Duration::from_millis(1)).await;
+
+        assert_eq!(batch, Some(vec![0, 1, 2, 3, 4]));
+    }
+
+    #[tokio::test]
+    async fn test_collect_batch_closed_channel() {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<u32>();
+        drop(sender);
+
+        assert_eq!(
+            collect_batch(&mut receiver, Duration::from_millis(1)).await,
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let create_message = |message_id: &str| GigaChatMessage {
+            message_id: message_id.to_string(),
+            ..GigaChatMessage::default()
+        };
+        let create_update = |message_id: &str, repeat_count: u32| UpdateMessageEventArgs {
+            message_id: message_id.to_string(),
+            repeat_count,
+        };
+
+        let (new_messages, updates) = merge_batch(vec![
+            ChatEvent::MessageUpdated(create_update("id0", 4)),
+            ChatEvent::NewMessage(create_message("id1")),
+            ChatEvent::MessageUpdated(create_update("id1", 2)),
+            ChatEvent::NewMessage(create_message("id2")),
+            ChatEvent::MessageUpdated(create_update("id1", 3)),
+        ]);
+
+        assert_eq!(new_messages.len(), 2);
+        assert_eq!(new_messages[0].repeat_count, 3);
+        assert_eq!(new_messages[1].repeat_count, 1);
+        assert_eq!(updates, vec![create_update("id0", 4)]);
+    }
+
+    #[test]
+    fn test_group_by_window() {
+        let create_update = |message_id: &str| {
+            ChatEvent::MessageUpdated(UpdateMessageEventArgs {
+                message_id: message_id.to_string(),
+                repeat_count: 2,
+            })
+        };
+
+        let groups = group_by_window(vec![
+            WindowChatEvent::new("main", create_update("id1")),
+            WindowChatEvent::new("overlay-subs", create_update("id2")),
+            WindowChatEvent::new("main", create_update("id3")),
+        ]);
+
+        let labels: Vec<&str> = groups.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec!["main", "overlay-subs"]);
+        assert_eq!(groups[0].1.len(), 2);
+        assert_eq!(groups[1].1.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_recorded_burst() {
+        let recorded_burst = parse_recorded_burst(include_str!("../../fixtures/chat_burst.irc"));
+
+        assert_eq!(recorded_burst.len(), 400);
+        assert_eq!(recorded_burst[0].0, Duration::ZERO);
+        assert!(recorded_burst.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+}
@@ -1,5 +1,33 @@
+mod control_api_service;
+mod dedup_service;
 mod emotes_service;
+mod hotkeys_service;
+mod ingestion_service;
+mod interaction_service;
+mod live_emotes_service;
+mod messages_service;
+mod overlay_server_service;
+mod overlay_windows_service;
+mod profiles_service;
 mod settings_service;
+mod sounds_service;
+mod stats_service;
+mod theme_service;
+mod tts_service;
 
-pub use emotes_service::EmotesService;
+pub use control_api_service::ControlApiService;
+pub use dedup_service::DedupService;
+pub use emotes_service::{EmoteRenderOptions, EmotesService, MessageEmote};
+pub use hotkeys_service::HotkeysService;
+pub use ingestion_service::IngestionService;
+pub use interaction_service::InteractionService;
+pub use live_emotes_service::LiveEmotesService;
+pub use messages_service::{ChatEvent, MessagesSender, MessagesService, WindowChatEvent};
+pub use overlay_server_service::{OverlayEvent, OverlayServerService};
+pub use overlay_windows_service::{OverlayWindowsService, MAIN_WINDOW_LABEL};
+pub use profiles_service::ProfilesService;
 pub use settings_service::SettingsService;
+pub use sounds_service::SoundsService;
+pub use stats_service::StatsService;
+pub use theme_service::ThemeService;
+pub use tts_service::TtsService;
@@ -0,0 +1,372 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    sync::Mutex,
+};
+
+use anyhow::anyhow;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use gigachat_models::{AppSettings, GigaChatMessage, UpdateMessageEventArgs};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::services::{ControlApiService, StatsService, ThemeService};
+
+// Events which are not picked up by a lagging browser source in time are skipped
+const EVENTS_CAPACITY: usize = 256;
+
+// Same names and payloads as Tauri events, so the UI handles both the same way
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "payload", rename_all = "snake_case")]
+pub enum OverlayEvent {
+    InitSettings(AppSettings),
+    SyncSettings(AppSettings),
+    NewMessages(Vec<GigaChatMessage>),
+    MessageUpdated(UpdateMessageEventArgs),
+    ClearChat,
+    ThemeUpdated,
+}
+
+impl OverlayEvent {
+    // Browser source routes are open to any local process, so control API token is never sent
+    fn redacted(self) -> Self {
+        let redact = |settings: AppSettings| AppSettings {
+            control_api_token: String::new(),
+            ..settings
+        };
+
+        match self {
+            OverlayEvent::InitSettings(settings) => OverlayEvent::InitSettings(redact(settings)),
+            OverlayEvent::SyncSettings(settings) => OverlayEvent::SyncSettings(redact(settings)),
+            event => event,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemeQuery {
+    theme: String,
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    channel: String,
+}
+
+struct RunningServer {
+    port: u16,
+    overlay_enabled: bool,
+    // Kept to restart the server on the same port, while the old one may still hold the socket
+    listener: TcpListener,
+    shutdown: oneshot::Sender<()>,
+}
+
+// Localhost only, browser source routes have no authentication.
+// Bound right away, so that a busy port is reported to the caller
+fn bind_listener(port: u16) -> anyhow::Result<TcpListener> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))?;
+    listener.set_nonblocking(true)?;
+
+    Ok(listener)
+}
+
+// Server is shared with control API, browser source routes are mounted only when enabled
+fn get_router(app_handle: AppHandle, overlay_enabled: bool) -> Router {
+    let router = Router::new().nest("/api", ControlApiService::get_router());
+
+    let router = if overlay_enabled {
+        router
+            .route("/ws", get(events_handler))
+            .route("/theme.css", get(theme_css_handler))
+            .route("/stats.json", get(stats_handler))
+            .fallback(asset_handler)
+    } else {
+        router
+    };
+
+    router.with_state(app_handle)
+}
+
+async fn send_event(socket: &mut WebSocket, event: &OverlayEvent) -> anyhow::Result<()> {
+    let text = serde_json::to_string(event)?;
+    socket.send(Message::Text(text)).await?;
+
+    Ok(())
+}
+
+async fn handle_socket(mut socket: WebSocket, app_handle: AppHandle) {
+    let mut events = app_handle.state::<OverlayServerService>().subscribe();
+    let settings = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .clone();
+
+    if send_event(
+        &mut socket,
+        &OverlayEvent::InitSettings(settings).redacted(),
+    )
+    .await
+    .is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if send_event(&mut socket, &event).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Browser source skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Browser sources don't send anything, reading only detects closed connections
+            message = socket.recv() => {
+                if matches!(message, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn events_handler(ws: WebSocketUpgrade, State(app_handle): State<AppHandle>) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, app_handle))
+}
+
+async fn theme_css_handler(
+    State(app_handle): State<AppHandle>,
+    Query(query): Query<ThemeQuery>,
+) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/css")],
+        ThemeService::get_theme_css(&app_handle, &query.theme),
+    )
+        .into_response()
+}
+
+async fn stats_handler(
+    State(app_handle): State<AppHandle>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    Json(StatsService::get_channel_stats(&app_handle, &query.channel)).into_response()
+}
+
+// Serves the same frontend bundle as app windows
+async fn asset_handler(State(app_handle): State<AppHandle>, uri: Uri) -> Response {
+    match app_handle.asset_resolver().get(uri.path().to_string()) {
+        Some(asset) => ([(header::CONTENT_TYPE, asset.mime_type)], asset.bytes).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// Local HTTP server which renders the chat for OBS browser sources and hosts control API
+pub struct OverlayServerService {
+    events: broadcast::Sender<OverlayEvent>,
+    server: Mutex<Option<RunningServer>>,
+}
+
+impl OverlayServerService {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        Self {
+            events,
+            server: Mutex::new(None),
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<OverlayEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn publish(&self, event: OverlayEvent) {
+        // Fails only when no browser source is connected
+        let _ = self.events.send(event.redacted());
+    }
+
+    // Starts, stops or moves the server to another port according to settings.
+    // Fails when the port can't be bound, the next call tries again
+    pub fn apply_settings(
+        &self,
+        app_handle: &AppHandle,
+        settings: &AppSettings,
+    ) -> anyhow::Result<()> {
+        let mut server = self.server.lock().unwrap();
+        let port = (settings.overlay_server_enabled || settings.control_api_enabled)
+            .then_some(settings.overlay_server_port);
+        let overlay_enabled = settings.overlay_server_enabled;
+
+        if server
+            .as_ref()
+            .map(|server| (server.port, server.overlay_enabled))
+            == port.map(|port| (port, overlay_enabled))
+        {
+            return Ok(());
+        }
+
+        let same_port_listener = server.take().and_then(|running_server| {
+            info!("Stopping overlay server on port {}", running_server.port);
+            let _ = running_server.shutdown.send(());
+
+            (Some(running_server.port) == port).then_some(running_server.listener)
+        });
+
+        if let Some(port) = port {
+            let listener = match same_port_listener {
+                Some(listener) => listener,
+                None => bind_listener(port).map_err(|err| {
+                    anyhow!("Failed to start overlay server on port {}: {}", port, err)
+                })?,
+            };
+
+            *server = Some(Self::start(
+                app_handle.clone(),
+                listener,
+                port,
+                overlay_enabled,
+            )?);
+        }
+
+        Ok(())
+    }
+
+    fn start(
+        app_handle: AppHandle,
+        listener: TcpListener,
+        port: u16,
+        overlay_enabled: bool,
+    ) -> anyhow::Result<RunningServer> {
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+        let server_listener = listener.try_clone()?;
+
+        tauri::async_runtime::spawn(async move {
+            let server = match axum::Server::from_tcp(server_listener) {
+                Ok(server) => server,
+                Err(err) => {
+                    error!("Failed to start overlay server on port {}: {:?}", port, err);
+                    return;
+                }
+            };
+
+            let router = get_router(app_handle, overlay_enabled);
+
+            info!("Overlay server is listening on http://127.0.0.1:{}", port);
+
+            let result = server
+                .serve(router.into_make_service())
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_receiver.await;
+                })
+                .await;
+
+            if let Err(err) = result {
+                error!("Overlay server failed: {:?}", err);
+            }
+        });
+
+        Ok(RunningServer {
+            port,
+            overlay_enabled,
+            listener,
+            shutdown,
+        })
+    }
+}
+
+impl Default for OverlayServerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_overlay_event_format() {
+        assert_eq!(
+            serde_json::to_value(OverlayEvent::ClearChat).unwrap(),
+            json!({ "event": "clear_chat" })
+        );
+        assert_eq!(
+            serde_json::to_value(OverlayEvent::MessageUpdated(UpdateMessageEventArgs {
+                message_id: "id1".to_string(),
+                repeat_count: 2,
+            }))
+            .unwrap(),
+            json!({
+                "event": "message_updated",
+                "payload": { "message_id": "id1", "repeat_count": 2 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_settings_events_are_redacted() {
+        let settings = AppSettings {
+            control_api_token: "secret".to_string(),
+            ..AppSettings::default()
+        };
+
+        match OverlayEvent::SyncSettings(settings).redacted() {
+            OverlayEvent::SyncSettings(settings) => assert!(settings.control_api_token.is_empty()),
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_bind_listener_busy_port() {
+        let listener = bind_listener(0).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(bind_listener(port).is_err());
+
+        drop(listener);
+        assert!(bind_listener(port).is_ok());
+    }
+
+    #[test]
+    fn test_kept_listener_holds_port() {
+        let listener = bind_listener(0).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_listener = listener.try_clone().unwrap();
+
+        // Stopped server drops its socket, the kept one still owns the port for the restart
+        drop(server_listener);
+        assert!(bind_listener(port).is_err());
+        assert_eq!(listener.local_addr().unwrap().port(), port);
+    }
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let overlay_server = OverlayServerService::new();
+
+        // No subscribers yet, event is dropped
+        overlay_server.publish(OverlayEvent::ThemeUpdated);
+
+        let mut events = overlay_server.subscribe();
+        overlay_server.publish(OverlayEvent::ClearChat);
+
+        assert!(matches!(events.try_recv(), Ok(OverlayEvent::ClearChat)));
+        assert!(events.try_recv().is_err());
+    }
+}
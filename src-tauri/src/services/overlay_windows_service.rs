@@ -0,0 +1,306 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use gigachat_models::{fnv1a_hash, AppSettings};
+use tauri::{AppHandle, Manager, Window};
+use tauri_plugin_window_state::{AppHandleExt, StateFlags, WindowExt};
+use twitch_irc::{login::StaticLoginCredentials, SecureTCPTransport, TwitchIRCClient};
+
+use crate::{
+    services::ProfilesService,
+    state::{overlay_windows_state::OverlayWindowsState, ui_lock_state::UiLockState},
+    utils::emit_sync_window_settings,
+};
+
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+const OVERLAY_WINDOW_LABEL_PREFIX: &str = "overlay-";
+
+// Window labels allow only a subset of characters, which profile names may contain.
+// Hash of the full name keeps labels of e.g. `Subs Raids` and `Subs_Raids` apart,
+// it's stable as window positions are saved under labels
+fn get_overlay_label(profile_name: &str) -> String {
+    let profile_name = profile_name.trim();
+    let name: String = profile_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!(
+        "{}{}-{:08x}",
+        OVERLAY_WINDOW_LABEL_PREFIX,
+        name,
+        fnv1a_hash(profile_name)
+    )
+}
+
+fn is_overlay_label(label: &str) -> bool {
+    label == MAIN_WINDOW_LABEL || label.starts_with(OVERLAY_WINDOW_LABEL_PREFIX)
+}
+
+// Channels which are shown in at least one overlay window
+fn get_wanted_channels<'a>(
+    main_settings: &'a AppSettings,
+    overlays_settings: impl Iterator<Item = &'a AppSettings>,
+) -> HashSet<String> {
+    std::iter::once(main_settings)
+        .chain(overlays_settings)
+        .map(|settings| settings.channel_name.clone())
+        .filter(|channel_name| !channel_name.is_empty())
+        .collect()
+}
+
+fn create_window(app_handle: &AppHandle, label: &str, profile_name: &str) {
+    let always_on_top = app_handle
+        .state::<Mutex<AppSettings>>()
+        .lock()
+        .unwrap()
+        .always_on_top;
+
+    let window = match tauri::WindowBuilder::new(app_handle, label, tauri::WindowUrl::default())
+        .title(format!("GigaChat - {}", profile_name))
+        .inner_size(400_f64, 400_f64)
+        .resizable(true)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(always_on_top)
+        .build()
+    {
+        Ok(window) => window,
+        Err(err) => {
+            error!("Failed to create overlay window '{}': {:?}", label, err);
+            return;
+        }
+    };
+
+    // Every overlay window keeps its own position and size, saved under its label
+    let _ = window.restore_state(StateFlags::all());
+
+    let is_ui_locked = *app_handle.state::<UiLockState>().lock().unwrap();
+    let _ = window.set_ignore_cursor_events(is_ui_locked);
+}
+
+// Additional overlay windows, each showing a saved profile with its own channel,
+// filters and style next to the main overlay
+pub struct OverlayWindowsService {}
+
+impl OverlayWindowsService {
+    // Main window and all additional overlay windows
+    pub fn get_overlay_windows(app_handle: &AppHandle) -> Vec<Window> {
+        app_handle
+            .windows()
+            .into_iter()
+            .filter(|(label, _)| is_overlay_label(label))
+            .map(|(_, window)| window)
+            .collect()
+    }
+
+    // Settings shown in the window, windows other than additional overlays use app settings
+    pub fn get_window_settings(app_handle: &AppHandle, window_label: &str) -> AppSettings {
+        let overlay_settings = app_handle
+            .state::<OverlayWindowsState>()
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .cloned();
+
+        overlay_settings.unwrap_or_else(|| {
+            app_handle
+                .state::<Mutex<AppSettings>>()
+                .lock()
+                .unwrap()
+                .clone()
+        })
+    }
+
+    // Labels and settings of overlay windows showing the channel
+    pub fn get_channel_windows(
+        app_handle: &AppHandle,
+        channel_login: &str,
+    ) -> Vec<(String, AppSettings)> {
+        let main_settings = app_handle
+            .state::<Mutex<AppSettings>>()
+            .lock()
+            .unwrap()
+            .clone();
+        let overlays = app_handle.state::<OverlayWindowsState>();
+        let overlays = overlays.lock().unwrap();
+
+        std::iter::once((MAIN_WINDOW_LABEL.to_string(), main_settings))
+            .chain(
+                overlays
+                    .iter()
+                    .map(|(label, settings)| (label.clone(), settings.clone())),
+            )
+            .filter(|(_, settings)| settings.channel_name == channel_login)
+            .collect()
+    }
+
+    // Joins channels of all overlay windows and leaves the ones nobody shows anymore
+    pub fn update_channels(app_handle: &AppHandle) {
+        let irc_client_state = match app_handle
+            .try_state::<Mutex<TwitchIRCClient<SecureTCPTransport, StaticLoginCredentials>>>()
+        {
+            Some(irc_client_state) => irc_client_state,
+            // Client is not started yet, it joins channels on start
+            None => return,
+        };
+
+        let channels = {
+            let main_settings = app_handle.state::<Mutex<AppSettings>>();
+            let main_settings = main_settings.lock().unwrap();
+            let overlays = app_handle.state::<OverlayWindowsState>();
+            let overlays = overlays.lock().unwrap();
+
+            get_wanted_channels(&main_settings, overlays.values())
+        };
+
+        debug!("Wanted channels: {:?}", channels);
+
+        if let Err(err) = irc_client_state
+            .lock()
+            .unwrap()
+            .set_wanted_channels(channels)
+        {
+            error!("Failed to join channels: {:?}", err);
+        }
+    }
+
+    // Opens windows for profiles listed in settings and closes the rest
+    pub fn apply_settings(app_handle: &AppHandle, settings: &AppSettings) {
+        let new_overlays: HashMap<String, (String, AppSettings)> = settings
+            .overlay_windows
+            .iter()
+            .filter_map(|profile_name| {
+                match ProfilesService::load_profile(app_handle, profile_name) {
+                    Ok(profile_settings) => Some((
+                        get_overlay_label(profile_name),
+                        (profile_name.clone(), profile_settings),
+                    )),
+                    Err(err) => {
+                        error!(
+                            "Failed to load overlay profile '{}': {:?}",
+                            profile_name, err
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let closed_labels: Vec<String> = {
+            let overlays = app_handle.state::<OverlayWindowsState>();
+            let mut overlays = overlays.lock().unwrap();
+            let closed_labels = overlays
+                .keys()
+                .filter(|label| !new_overlays.contains_key(*label))
+                .cloned()
+                .collect();
+
+            *overlays = new_overlays
+                .iter()
+                .map(|(label, (_, settings))| (label.clone(), settings.clone()))
+                .collect();
+
+            closed_labels
+        };
+
+        if !closed_labels.is_empty() {
+            if let Err(err) = app_handle.save_window_state(StateFlags::all()) {
+                error!("Failed to save window state: {:?}", err);
+            }
+        }
+
+        for label in closed_labels {
+            if let Some(window) = app_handle.get_window(&label) {
+                let _ = window.close();
+            }
+        }
+
+        for (label, (profile_name, profile_settings)) in new_overlays {
+            match app_handle.get_window(&label) {
+                Some(_) => emit_sync_window_settings(app_handle, &label, &profile_settings),
+                // Creating a window from the main thread (e.g. synchronous command or hotkey)
+                // deadlocks on Windows
+                None => {
+                    let app_handle = app_handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        create_window(&app_handle, &label, &profile_name);
+                    });
+                }
+            }
+        }
+
+        Self::update_channels(app_handle);
+    }
+
+    // Shows saved profile changes in its overlay window
+    pub fn update_profile(app_handle: &AppHandle, profile_name: &str, settings: &AppSettings) {
+        let label = get_overlay_label(profile_name);
+
+        {
+            let overlays = app_handle.state::<OverlayWindowsState>();
+            let mut overlays = overlays.lock().unwrap();
+
+            match overlays.get_mut(&label) {
+                Some(overlay_settings) => *overlay_settings = settings.clone(),
+                None => return,
+            }
+        }
+
+        emit_sync_window_settings(app_handle, &label, settings);
+        Self::update_channels(app_handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_overlay_label() {
+        assert!(get_overlay_label("subs").starts_with("overlay-subs-"));
+        assert!(get_overlay_label(" Subs & Raids ").starts_with("overlay-Subs___Raids-"));
+        assert_eq!(get_overlay_label(" subs "), get_overlay_label("subs"));
+        assert!(is_overlay_label(&get_overlay_label("subs")));
+        assert!(is_overlay_label(MAIN_WINDOW_LABEL));
+        assert!(!is_overlay_label("settings"));
+    }
+
+    #[test]
+    fn test_get_overlay_label_collisions() {
+        assert_ne!(
+            get_overlay_label("Subs Raids"),
+            get_overlay_label("Subs_Raids")
+        );
+        assert_ne!(get_overlay_label("Сабы"), get_overlay_label("Рейды"));
+    }
+
+    #[test]
+    fn test_get_wanted_channels() {
+        let create_settings = |channel_name: &str| AppSettings {
+            channel_name: channel_name.to_string(),
+            ..AppSettings::default()
+        };
+
+        let main_settings = create_settings("streamer");
+        let overlays_settings = vec![
+            create_settings("streamer"),
+            create_settings(""),
+            create_settings("friend"),
+        ];
+
+        assert_eq!(
+            get_wanted_channels(&main_settings, overlays_settings.iter()),
+            HashSet::from(["streamer".to_string(), "friend".to_string()])
+        );
+    }
+}
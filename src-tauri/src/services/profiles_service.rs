@@ -0,0 +1,166 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::anyhow;
+use gigachat_models::AppSettings;
+use tauri::AppHandle;
+
+use crate::services::SettingsService;
+
+// Profiles are named snapshots of app settings stored as separate files
+pub struct ProfilesService {}
+
+impl ProfilesService {
+    pub fn get_profiles_dir(app_handle: &AppHandle) -> PathBuf {
+        SettingsService::get_config_dir(app_handle).join("profiles")
+    }
+
+    fn get_profile_path(app_handle: &AppHandle, profile_name: &str) -> anyhow::Result<PathBuf> {
+        let is_valid_name = !profile_name.trim().is_empty()
+            && profile_name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_');
+
+        if !is_valid_name {
+            return Err(anyhow!("Invalid profile name: '{}'", profile_name));
+        }
+
+        Ok(Self::get_profiles_dir(app_handle).join(format!("{}.json", profile_name.trim())))
+    }
+
+    pub fn get_profiles(app_handle: &AppHandle) -> Vec<String> {
+        let entries = match fs::read_dir(Self::get_profiles_dir(app_handle)) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut profiles: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+
+        profiles.sort_unstable_by_key(|profile| profile.to_lowercase());
+
+        profiles
+    }
+
+    pub fn load_profile(app_handle: &AppHandle, profile_name: &str) -> anyhow::Result<AppSettings> {
+        let profile_path = Self::get_profile_path(app_handle, profile_name)?;
+        let profile_content = fs::read_to_string(profile_path)?;
+
+        Ok(SettingsService::normalize_settings(serde_json::from_str(
+            &profile_content,
+        )?))
+    }
+
+    // Profile settings combined with app-wide settings, which are not switched with profiles:
+    // global hotkeys, local server, additional overlay windows and emote providers
+    pub fn merge_profile(
+        profile_name: &str,
+        profile_settings: AppSettings,
+        current_settings: &AppSettings,
+    ) -> AppSettings {
+        AppSettings {
+            active_profile: profile_name.to_string(),
+            hotkeys: current_settings.hotkeys.clone(),
+            overlay_server_enabled: current_settings.overlay_server_enabled,
+            overlay_server_port: current_settings.overlay_server_port,
+            control_api_enabled: current_settings.control_api_enabled,
+            control_api_token: current_settings.control_api_token.clone(),
+            overlay_windows: current_settings.overlay_windows.clone(),
+            disabled_emote_providers: current_settings.disabled_emote_providers.clone(),
+            ..profile_settings
+        }
+    }
+
+    pub fn save_profile(
+        app_handle: &AppHandle,
+        profile_name: &str,
+        settings: &AppSettings,
+    ) -> anyhow::Result<()> {
+        let profile_path = Self::get_profile_path(app_handle, profile_name)?;
+
+        if let Some(parent) = profile_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(profile_path, serde_json::to_string_pretty(settings)?)?;
+
+        Ok(())
+    }
+
+    // Returns profile that goes after the active one, wrapping around
+    pub fn get_next_profile(profiles: &[String], active_profile: &str) -> Option<String> {
+        let next_index = profiles
+            .iter()
+            .position(|profile| profile == active_profile)
+            .map_or(0, |index| (index + 1) % profiles.len());
+
+        profiles.get(next_index).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_profiles() -> Vec<String> {
+        vec!["gaming".to_string(), "irl".to_string(), "music".to_string()]
+    }
+
+    #[test]
+    fn test_merge_profile_keeps_app_wide_settings() {
+        let current_settings = AppSettings {
+            channel_name: "current".to_string(),
+            control_api_enabled: true,
+            control_api_token: "secret".to_string(),
+            overlay_windows: vec!["subs".to_string()],
+            ..AppSettings::default()
+        };
+        let profile_settings = AppSettings {
+            channel_name: "profile".to_string(),
+            ..AppSettings::default()
+        };
+
+        let settings =
+            ProfilesService::merge_profile("gaming", profile_settings, &current_settings);
+
+        assert_eq!(settings.active_profile, "gaming");
+        assert_eq!(settings.channel_name, "profile");
+        assert!(settings.control_api_enabled);
+        assert_eq!(settings.control_api_token, "secret");
+        assert_eq!(settings.overlay_windows, vec!["subs".to_string()]);
+    }
+
+    #[test]
+    fn test_get_next_profile() {
+        let profiles = get_profiles();
+
+        assert_eq!(
+            ProfilesService::get_next_profile(&profiles, "gaming"),
+            Some("irl".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_next_profile_wraps_around() {
+        let profiles = get_profiles();
+
+        assert_eq!(
+            ProfilesService::get_next_profile(&profiles, "music"),
+            Some("gaming".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_next_profile_unknown_active_profile() {
+        let profiles = get_profiles();
+
+        assert_eq!(
+            ProfilesService::get_next_profile(&profiles, ""),
+            Some("gaming".to_string())
+        );
+        assert_eq!(ProfilesService::get_next_profile(&[], ""), None);
+    }
+}
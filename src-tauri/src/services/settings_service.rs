@@ -1,18 +1,29 @@
 use std::{fs, path::PathBuf};
 
 use gigachat_models::AppSettings;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
+
+use crate::cli::CliArgs;
 
 pub struct SettingsService {}
 
 impl SettingsService {
-    pub fn get_settings_path(app_handle: &AppHandle) -> PathBuf {
-        let app_settings_path = app_handle
+    pub fn get_config_dir(app_handle: &AppHandle) -> PathBuf {
+        if let Some(config_dir) = app_handle
+            .try_state::<CliArgs>()
+            .and_then(|cli_args| cli_args.config_dir.clone())
+        {
+            return config_dir;
+        }
+
+        app_handle
             .path_resolver()
             .app_config_dir()
-            .expect("Failed to get app config path");
+            .expect("Failed to get app config path")
+    }
 
-        app_settings_path.join("app_settings.json")
+    pub fn get_settings_path(app_handle: &AppHandle) -> PathBuf {
+        Self::get_config_dir(app_handle).join("app_settings.json")
     }
 
     pub fn get_settings(app_handle: &AppHandle) -> AppSettings {
@@ -21,16 +32,31 @@ impl SettingsService {
 
         if let Ok(settings) = settings_content {
             info!("Settings file found... loading");
-            serde_json::from_str(&settings).unwrap_or_else(|_| {
+            let settings = serde_json::from_str(&settings).unwrap_or_else(|_| {
                 error!("Failed to parse settings file, using defaults");
                 AppSettings::default()
-            })
+            });
+
+            Self::normalize_settings(settings)
         } else {
             info!("Settings file not found, using defaults");
             AppSettings::default()
         }
     }
 
+    // IRC channel logins are lowercase, while the name may be typed as `#Streamer`
+    pub fn normalize_channel_name(channel_name: &str) -> String {
+        channel_name.trim().trim_start_matches('#').to_lowercase()
+    }
+
+    // Applied wherever settings enter the app, so that they can be compared with IRC messages
+    pub fn normalize_settings(settings: AppSettings) -> AppSettings {
+        AppSettings {
+            channel_name: Self::normalize_channel_name(&settings.channel_name),
+            ..settings
+        }
+    }
+
     pub fn save_settings(app_handle: &AppHandle, settings: &AppSettings) -> anyhow::Result<()> {
         let app_settings_path = Self::get_settings_path(app_handle);
 
@@ -43,3 +69,26 @@ impl SettingsService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_channel_name() {
+        assert_eq!(
+            SettingsService::normalize_channel_name(" #Streamer "),
+            "streamer"
+        );
+    }
+
+    #[test]
+    fn test_normalize_settings_mixed_case() {
+        let settings = SettingsService::normalize_settings(AppSettings {
+            channel_name: "StreamerName".to_string(),
+            ..AppSettings::default()
+        });
+
+        assert_eq!(settings.channel_name, "streamername");
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, Cursor},
+    path::PathBuf,
+    sync::{mpsc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail};
+use gigachat_models::{AppSettings, SoundRule, SoundSettings};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use tauri::{AppHandle, Manager};
+use twitch_irc::message::{PrivmsgMessage, UserNoticeMessage};
+
+use crate::services::{MessagesService, SettingsService};
+
+const BUNDLED_SOUNDS: [(&str, &[u8]); 4] = [
+    ("chime", include_bytes!("../../sounds/chime.wav")),
+    ("ping", include_bytes!("../../sounds/ping.wav")),
+    ("pop", include_bytes!("../../sounds/pop.wav")),
+    ("fanfare", include_bytes!("../../sounds/fanfare.wav")),
+];
+
+const USER_SOUND_EXTENSIONS: [&str; 4] = ["wav", "mp3", "ogg", "flac"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SoundEvent {
+    Mention,
+    FirstMessage,
+    Subscription,
+    Raid,
+    Keyword,
+}
+
+impl SoundEvent {
+    fn get_rule(self, settings: &SoundSettings) -> &SoundRule {
+        match self {
+            SoundEvent::Mention => &settings.mention,
+            SoundEvent::FirstMessage => &settings.first_message,
+            SoundEvent::Subscription => &settings.subscription,
+            SoundEvent::Raid => &settings.raid,
+            SoundEvent::Keyword => &settings.keyword,
+        }
+    }
+}
+
+enum SoundData {
+    Bundled(&'static [u8]),
+    File(PathBuf),
+}
+
+struct PlayRequest {
+    data: SoundData,
+    volume: f32,
+}
+
+// Remembers when every event sound was played last time, so that bursts of events
+// (e.g. gifted subscriptions) don't turn into a wall of noise
+#[derive(Debug, Default)]
+struct Cooldowns {
+    last_played: HashMap<SoundEvent, Instant>,
+}
+
+impl Cooldowns {
+    fn try_start(&mut self, event: SoundEvent, cooldown: Duration, now: Instant) -> bool {
+        if let Some(last_played) = self.last_played.get(&event) {
+            if now.duration_since(*last_played) < cooldown {
+                return false;
+            }
+        }
+
+        self.last_played.insert(event, now);
+        true
+    }
+}
+
+fn contains_keyword(message_text: &str, keywords: &str) -> bool {
+    let message_text = message_text.to_lowercase();
+
+    keywords
+        .split(',')
+        .map(|keyword| keyword.trim().to_lowercase())
+        .any(|keyword| !keyword.is_empty() && message_text.contains(&keyword))
+}
+
+// Picks a single enabled event for chat message, mentions take priority over keywords
+// and keywords over first messages
+fn get_message_event(msg: &PrivmsgMessage, settings: &AppSettings) -> Option<SoundEvent> {
+    let sounds = &settings.sounds;
+
+    if sounds.mention.enabled
+        && MessagesService::is_mention(&msg.message_text, &settings.channel_name)
+    {
+        return Some(SoundEvent::Mention);
+    }
+
+    if sounds.keyword.enabled && contains_keyword(&msg.message_text, &sounds.keywords) {
+        return Some(SoundEvent::Keyword);
+    }
+
+    let is_first_message = msg
+        .source
+        .tags
+        .0
+        .get("first-msg")
+        .map_or(false, |first_msg| first_msg == "1");
+
+    if sounds.first_message.enabled && is_first_message {
+        return Some(SoundEvent::FirstMessage);
+    }
+
+    None
+}
+
+// Maps `msg-id` of USERNOTICE to sound event
+fn get_user_notice_event(event_id: &str) -> Option<SoundEvent> {
+    match event_id {
+        "sub"
+        | "resub"
+        | "subgift"
+        | "submysterygift"
+        | "giftpaidupgrade"
+        | "anongiftpaidupgrade" => Some(SoundEvent::Subscription),
+        "raid" => Some(SoundEvent::Raid),
+        _ => None,
+    }
+}
+
+fn play_sound(stream_handle: &OutputStreamHandle, request: PlayRequest) -> anyhow::Result<()> {
+    let sink = Sink::try_new(stream_handle)?;
+
+    match request.data {
+        SoundData::Bundled(bytes) => sink.append(Decoder::new(Cursor::new(bytes))?),
+        SoundData::File(path) => sink.append(Decoder::new(BufReader::new(File::open(path)?))?),
+    }
+
+    sink.set_volume(request.volume.clamp(0_f32, 1_f32));
+    sink.detach();
+
+    Ok(())
+}
+
+pub struct SoundsService {
+    sender: Mutex<mpsc::Sender<PlayRequest>>,
+    cooldowns: Mutex<Cooldowns>,
+}
+
+impl SoundsService {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<PlayRequest>();
+
+        // Audio output stream can't be moved between threads, so it's owned by the player thread
+        thread::spawn(move || {
+            let (_stream, stream_handle) = match OutputStream::try_default() {
+                Ok(output) => output,
+                Err(err) => {
+                    error!("Failed to open audio output: {:?}", err);
+                    return;
+                }
+            };
+
+            for request in receiver {
+                if let Err(err) = play_sound(&stream_handle, request) {
+                    error!("Failed to play sound: {:?}", err);
+                }
+            }
+        });
+
+        Self {
+            sender: Mutex::new(sender),
+            cooldowns: Mutex::new(Cooldowns::default()),
+        }
+    }
+
+    pub fn get_user_sounds_dir(app_handle: &AppHandle) -> PathBuf {
+        SettingsService::get_config_dir(app_handle).join("sounds")
+    }
+
+    // Bundled sounds followed by supported files from the user sounds directory
+    pub fn get_sound_names(app_handle: &AppHandle) -> Vec<String> {
+        let mut user_sounds: Vec<String> = fs::read_dir(Self::get_user_sounds_dir(app_handle))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().map_or(false, |extension| {
+                            USER_SOUND_EXTENSIONS
+                                .contains(&extension.to_string_lossy().to_lowercase().as_str())
+                        })
+                    })
+                    .filter_map(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        user_sounds.sort();
+
+        BUNDLED_SOUNDS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(user_sounds)
+            .collect()
+    }
+
+    fn get_sound_data(app_handle: &AppHandle, sound_name: &str) -> anyhow::Result<SoundData> {
+        if let Some((_, bytes)) = BUNDLED_SOUNDS.iter().find(|(name, _)| *name == sound_name) {
+            return Ok(SoundData::Bundled(bytes));
+        }
+
+        // Only plain file names, so that settings can't point outside of the sounds directory
+        let path = Self::get_user_sounds_dir(app_handle).join(sound_name);
+        if sound_name.contains(['/', '\\']) || !path.is_file() {
+            bail!("Unknown sound '{}'", sound_name);
+        }
+
+        Ok(SoundData::File(path))
+    }
+
+    pub fn play(
+        &self,
+        app_handle: &AppHandle,
+        sound_name: &str,
+        volume: f32,
+    ) -> anyhow::Result<()> {
+        let data = Self::get_sound_data(app_handle, sound_name)?;
+
+        // Player thread exits when there is no audio output device
+        self.sender
+            .lock()
+            .unwrap()
+            .send(PlayRequest { data, volume })
+            .map_err(|_| anyhow!("Audio output is not available"))
+    }
+
+    fn notify(&self, app_handle: &AppHandle, event: SoundEvent, settings: &SoundSettings) {
+        let rule = event.get_rule(settings);
+        if !rule.enabled {
+            return;
+        }
+
+        let cooldown = Duration::from_secs(settings.cooldown_secs as u64);
+        if !self
+            .cooldowns
+            .lock()
+            .unwrap()
+            .try_start(event, cooldown, Instant::now())
+        {
+            return;
+        }
+
+        if let Err(err) = self.play(app_handle, &rule.sound, rule.volume) {
+            warn!("Failed to play {:?} sound: {:?}", event, err);
+        }
+    }
+
+    pub fn notify_message(&self, app_handle: &AppHandle, msg: &PrivmsgMessage) {
+        let settings_state = app_handle.state::<Mutex<AppSettings>>();
+        let settings = settings_state.lock().unwrap().clone();
+
+        // Alerts belong to the main overlay, channels of additional overlay windows are silent
+        if msg.channel_login != settings.channel_name {
+            return;
+        }
+
+        if let Some(event) = get_message_event(msg, &settings) {
+            self.notify(app_handle, event, &settings.sounds);
+        }
+    }
+
+    pub fn notify_user_notice(&self, app_handle: &AppHandle, msg: &UserNoticeMessage) {
+        let settings_state = app_handle.state::<Mutex<AppSettings>>();
+        let (channel_name, sound_settings) = {
+            let settings = settings_state.lock().unwrap();
+            (settings.channel_name.clone(), settings.sounds.clone())
+        };
+
+        if msg.channel_login != channel_name {
+            return;
+        }
+
+        if let Some(event) = get_user_notice_event(&msg.event_id) {
+            self.notify(app_handle, event, &sound_settings);
+        }
+    }
+}
+
+impl Default for SoundsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils;
+
+    fn create_privmsg(first_msg: bool, text: &str) -> PrivmsgMessage {
+        test_utils::create_privmsg(&format!("first-msg={}", first_msg as u8), text)
+    }
+
+    fn create_settings() -> AppSettings {
+        let mut settings = AppSettings {
+            channel_name: "streamer".to_string(),
+            ..AppSettings::default()
+        };
+
+        settings.sounds.mention.enabled = true;
+        settings.sounds.first_message.enabled = true;
+        settings.sounds.keyword.enabled = true;
+        settings.sounds.keywords = "giveaway, Drops".to_string();
+
+        settings
+    }
+
+    #[test]
+    fn test_get_message_event_priority() {
+        let settings = create_settings();
+
+        assert_eq!(
+            get_message_event(&create_privmsg(true, "@streamer any drops?"), &settings),
+            Some(SoundEvent::Mention)
+        );
+        assert_eq!(
+            get_message_event(&create_privmsg(true, "any DROPS today?"), &settings),
+            Some(SoundEvent::Keyword)
+        );
+        assert_eq!(
+            get_message_event(&create_privmsg(true, "hello"), &settings),
+            Some(SoundEvent::FirstMessage)
+        );
+        assert_eq!(
+            get_message_event(&create_privmsg(false, "hello"), &settings),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_message_event_skips_disabled_rules() {
+        let mut settings = create_settings();
+        settings.sounds.mention.enabled = false;
+
+        assert_eq!(
+            get_message_event(&create_privmsg(false, "@streamer giveaway?"), &settings),
+            Some(SoundEvent::Keyword)
+        );
+    }
+
+    #[test]
+    fn test_contains_keyword_ignores_empty_keywords() {
+        assert!(!contains_keyword("hello", ""));
+        assert!(!contains_keyword("hello", " , ,"));
+        assert!(contains_keyword("Free GIVEAWAY", "drops,giveaway"));
+    }
+
+    #[test]
+    fn test_get_user_notice_event() {
+        assert_eq!(
+            get_user_notice_event("resub"),
+            Some(SoundEvent::Subscription)
+        );
+        assert_eq!(
+            get_user_notice_event("subgift"),
+            Some(SoundEvent::Subscription)
+        );
+        assert_eq!(get_user_notice_event("raid"), Some(SoundEvent::Raid));
+        assert_eq!(get_user_notice_event("announcement"), None);
+    }
+
+    #[test]
+    fn test_cooldowns() {
+        let mut cooldowns = Cooldowns::default();
+        let cooldown = Duration::from_secs(5);
+        let now = Instant::now();
+
+        assert!(cooldowns.try_start(SoundEvent::Raid, cooldown, now));
+        assert!(!cooldowns.try_start(SoundEvent::Raid, cooldown, now + Duration::from_secs(4)));
+        assert!(cooldowns.try_start(SoundEvent::Mention, cooldown, now));
+        assert!(cooldowns.try_start(SoundEvent::Raid, cooldown, now + cooldown));
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use gigachat_models::{ChatStats, ChatterStat, EmoteStat};
+use tauri::{AppHandle, Manager};
+
+use crate::services::{MessageEmote, SettingsService};
+
+// Top emotes and chatters are counted over this window
+const STATS_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+// Messages per minute are counted over the last minute only, so they follow bursts
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+const TOP_COUNT: usize = 5;
+
+// Keeps memory bounded in huge chats, the oldest messages are dropped first
+const MAX_TRACKED_MESSAGES: usize = 20_000;
+
+#[derive(Debug)]
+struct StatsMessage {
+    login: String,
+    display_name: String,
+    emotes: Vec<MessageEmote>,
+    received_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ChannelStats {
+    messages: VecDeque<StatsMessage>,
+}
+
+impl ChannelStats {
+    fn remove_expired(&mut self, now: Instant) {
+        while let Some(message) = self.messages.front() {
+            if now.duration_since(message.received_at) < STATS_WINDOW {
+                break;
+            }
+
+            self.messages.pop_front();
+        }
+    }
+
+    fn get_stats(&self, now: Instant) -> ChatStats {
+        let messages_per_minute = self
+            .messages
+            .iter()
+            .rev()
+            .take_while(|message| now.duration_since(message.received_at) < RATE_WINDOW)
+            .count();
+
+        let mut emotes: HashMap<&str, EmoteStat> = HashMap::new();
+        let mut chatters: HashMap<&str, ChatterStat> = HashMap::new();
+
+        for message in &self.messages {
+            for emote in &message.emotes {
+                let emote_stat = emotes.entry(&emote.code).or_insert_with(|| EmoteStat {
+                    code: emote.code.clone(),
+                    url: emote.url.clone(),
+                    count: 0,
+                });
+                emote_stat.count += 1;
+            }
+
+            let chatter_stat = chatters
+                .entry(&message.login)
+                .or_insert_with(|| ChatterStat {
+                    login: message.login.clone(),
+                    display_name: String::new(),
+                    message_count: 0,
+                });
+            // Latest display name, in case the chatter changed their capitalization
+            chatter_stat.display_name = message.display_name.clone();
+            chatter_stat.message_count += 1;
+        }
+
+        let unique_chatters = chatters.len();
+
+        let mut top_emotes: Vec<EmoteStat> = emotes.into_values().collect();
+        top_emotes.sort_by(|a, b| (Reverse(a.count), &a.code).cmp(&(Reverse(b.count), &b.code)));
+        top_emotes.truncate(TOP_COUNT);
+
+        let mut top_chatters: Vec<ChatterStat> = chatters.into_values().collect();
+        top_chatters.sort_by(|a, b| {
+            (Reverse(a.message_count), &a.login).cmp(&(Reverse(b.message_count), &b.login))
+        });
+        top_chatters.truncate(TOP_COUNT);
+
+        ChatStats {
+            window_secs: STATS_WINDOW.as_secs(),
+            messages_per_minute: messages_per_minute as u32,
+            unique_chatters: unique_chatters as u32,
+            top_emotes,
+            top_chatters,
+        }
+    }
+}
+
+// Chat activity of joined channels over sliding windows, for the stats bar
+#[derive(Debug, Default)]
+pub struct StatsService {
+    channels: Mutex<HashMap<String, ChannelStats>>,
+}
+
+impl StatsService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_message(
+        &self,
+        channel_login: &str,
+        login: &str,
+        display_name: &str,
+        emotes: Vec<MessageEmote>,
+    ) {
+        self.record_message_at(channel_login, login, display_name, emotes, Instant::now());
+    }
+
+    fn record_message_at(
+        &self,
+        channel_login: &str,
+        login: &str,
+        display_name: &str,
+        emotes: Vec<MessageEmote>,
+        now: Instant,
+    ) {
+        let mut channels = self.channels.lock().unwrap();
+        let channel_stats = channels.entry(channel_login.to_string()).or_default();
+
+        channel_stats.remove_expired(now);
+
+        if channel_stats.messages.len() >= MAX_TRACKED_MESSAGES {
+            channel_stats.messages.pop_front();
+        }
+
+        channel_stats.messages.push_back(StatsMessage {
+            login: login.to_string(),
+            display_name: display_name.to_string(),
+            emotes,
+            received_at: now,
+        });
+    }
+
+    pub fn get_stats(&self, channel_login: &str) -> ChatStats {
+        self.get_stats_at(channel_login, Instant::now())
+    }
+
+    fn get_stats_at(&self, channel_login: &str, now: Instant) -> ChatStats {
+        let mut channels = self.channels.lock().unwrap();
+
+        match channels.get_mut(channel_login) {
+            Some(channel_stats) => {
+                channel_stats.remove_expired(now);
+                channel_stats.get_stats(now)
+            }
+            None => ChatStats {
+                window_secs: STATS_WINDOW.as_secs(),
+                ..ChatStats::default()
+            },
+        }
+    }
+
+    // Channel name comes from the UI, logins of IRC messages are lowercase
+    pub fn get_channel_stats(app_handle: &AppHandle, channel_name: &str) -> ChatStats {
+        app_handle
+            .state::<StatsService>()
+            .get_stats(&SettingsService::normalize_channel_name(channel_name))
+    }
+
+    pub fn reset_channel(&self, channel_login: &str) {
+        self.channels.lock().unwrap().remove(channel_login);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_emotes(codes: &[&str]) -> Vec<MessageEmote> {
+        codes
+            .iter()
+            .map(|code| MessageEmote {
+                code: code.to_string(),
+                url: format!("_{}_url_", code),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_stats_counts() {
+        let stats_service = StatsService::new();
+        let now = Instant::now();
+
+        stats_service.record_message_at("chan", "a", "A", create_emotes(&["Pog", "KEKW"]), now);
+        stats_service.record_message_at("chan", "b", "B", create_emotes(&["KEKW"]), now);
+        stats_service.record_message_at("chan", "a", "A", create_emotes(&["KEKW"]), now);
+        stats_service.record_message_at("other", "c", "C", create_emotes(&["Pog"]), now);
+
+        let stats = stats_service.get_stats_at("chan", now);
+
+        assert_eq!(stats.messages_per_minute, 3);
+        assert_eq!(stats.unique_chatters, 2);
+        assert_eq!(
+            stats
+                .top_emotes
+                .iter()
+                .map(|emote| (emote.code.as_str(), emote.url.as_str(), emote.count))
+                .collect::<Vec<_>>(),
+            vec![("KEKW", "_KEKW_url_", 3), ("Pog", "_Pog_url_", 1)]
+        );
+        assert_eq!(
+            stats
+                .top_chatters
+                .iter()
+                .map(|chatter| (chatter.login.as_str(), chatter.message_count))
+                .collect::<Vec<_>>(),
+            vec![("a", 2), ("b", 1)]
+        );
+    }
+
+    #[test]
+    fn test_get_stats_sliding_windows() {
+        let stats_service = StatsService::new();
+        let start = Instant::now();
+
+        stats_service.record_message_at("chan", "a", "A", create_emotes(&["Pog"]), start);
+        stats_service.record_message_at(
+            "chan",
+            "b",
+            "B",
+            create_emotes(&["KEKW"]),
+            start + Duration::from_secs(200),
+        );
+
+        // Both messages are within the stats window, only the second one within the rate window
+        let stats = stats_service.get_stats_at("chan", start + Duration::from_secs(240));
+        assert_eq!(stats.messages_per_minute, 1);
+        assert_eq!(stats.unique_chatters, 2);
+        assert_eq!(stats.top_emotes.len(), 2);
+
+        let stats = stats_service.get_stats_at("chan", start + STATS_WINDOW);
+        assert_eq!(stats.messages_per_minute, 0);
+        assert_eq!(stats.unique_chatters, 1);
+        assert_eq!(stats.top_emotes[0].code, "KEKW");
+    }
+
+    #[test]
+    fn test_get_stats_top_count_and_unknown_channel() {
+        let stats_service = StatsService::new();
+        let now = Instant::now();
+
+        for login in ["a", "b", "c", "d", "e", "f", "g"] {
+            stats_service.record_message_at("chan", login, login, vec![], now);
+        }
+
+        let stats = stats_service.get_stats_at("chan", now);
+        assert_eq!(stats.unique_chatters, 7);
+        assert_eq!(stats.top_chatters.len(), TOP_COUNT);
+        assert_eq!(stats.top_chatters[0].login, "a");
+
+        stats_service.reset_channel("chan");
+
+        let stats = stats_service.get_stats_at("chan", now);
+        assert_eq!(stats.unique_chatters, 0);
+        assert_eq!(stats.window_secs, STATS_WINDOW.as_secs());
+    }
+}
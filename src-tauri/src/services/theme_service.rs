@@ -0,0 +1,76 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use tauri::AppHandle;
+
+use crate::{services::SettingsService, utils::emit_theme_updated};
+
+const BUNDLED_THEMES: [(&str, &str); 3] = [
+    ("default", include_str!("../../themes/default.css")),
+    ("compact", include_str!("../../themes/compact.css")),
+    ("bubbles", include_str!("../../themes/bubbles.css")),
+];
+
+const USER_THEME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct ThemeService {}
+
+impl ThemeService {
+    pub fn get_bundled_theme_names() -> Vec<String> {
+        BUNDLED_THEMES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    pub fn get_user_theme_path(app_handle: &AppHandle) -> PathBuf {
+        SettingsService::get_config_dir(app_handle).join("theme.css")
+    }
+
+    // Returns bundled theme css followed by the user stylesheet, so user rules win
+    pub fn get_theme_css(app_handle: &AppHandle, theme_name: &str) -> String {
+        let bundled_css = BUNDLED_THEMES
+            .iter()
+            .find(|(name, _)| *name == theme_name)
+            .map_or_else(
+                || {
+                    warn!("Unknown theme '{}', using default", theme_name);
+                    BUNDLED_THEMES[0].1
+                },
+                |(_, css)| *css,
+            );
+
+        let user_css =
+            fs::read_to_string(Self::get_user_theme_path(app_handle)).unwrap_or_default();
+
+        format!("{}\n{}", bundled_css, user_css)
+    }
+
+    // Polls user theme file and notifies windows when it is created, changed or removed
+    pub fn watch_user_theme(app_handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let user_theme_path = Self::get_user_theme_path(&app_handle);
+            let get_modified_time = |path: &PathBuf| -> Option<SystemTime> {
+                fs::metadata(path).and_then(|meta| meta.modified()).ok()
+            };
+
+            let mut last_modified = get_modified_time(&user_theme_path);
+            let mut interval = tokio::time::interval(USER_THEME_POLL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let modified = get_modified_time(&user_theme_path);
+                if modified != last_modified {
+                    info!("User theme changed: {:?}", user_theme_path);
+
+                    last_modified = modified;
+                    emit_theme_updated(&app_handle);
+                }
+            }
+        });
+    }
+}
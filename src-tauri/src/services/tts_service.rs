@@ -0,0 +1,276 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use gigachat_models::{fnv1a_hash, AppSettings, TtsPolicy};
+use tauri::{AppHandle, Manager};
+use twitch_irc::message::PrivmsgMessage;
+
+use crate::{
+    services::{EmotesService, MessagesService},
+    state::saved_emotes_state::EmotesState,
+    tts_engines::{TtsEngine, TtsVoice},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Older messages are dropped, so that speech doesn't fall too far behind the chat
+const MAX_QUEUE_LENGTH: usize = 10;
+
+const DEFAULT_PITCH: u32 = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TtsItem {
+    text: String,
+    voice: TtsVoice,
+}
+
+#[derive(Debug, Default)]
+struct TtsQueue {
+    items: VecDeque<TtsItem>,
+    skip_current: bool,
+}
+
+fn matches_policy(msg: &PrivmsgMessage, policy: TtsPolicy, channel_name: &str) -> bool {
+    match policy {
+        TtsPolicy::All => true,
+        TtsPolicy::Highlights => msg
+            .source
+            .tags
+            .0
+            .get("msg-id")
+            .map_or(false, |msg_id| msg_id == "highlighted-message"),
+        TtsPolicy::Mentions => MessagesService::is_mention(&msg.message_text, channel_name),
+        TtsPolicy::SubsAndBits => {
+            msg.bits.is_some()
+                || msg
+                    .badges
+                    .iter()
+                    .any(|badge| badge.name == "subscriber" || badge.name == "founder")
+        }
+    }
+}
+
+// Text which is read aloud: sender name and message without emotes and links
+fn get_speech_text(msg: &PrivmsgMessage, is_emote: impl Fn(&str) -> bool) -> Option<String> {
+    let words: Vec<&str> = msg
+        .message_text
+        .split_whitespace()
+        .filter(|word| !is_emote(word))
+        .map(|word| {
+            if word.starts_with("http://") || word.starts_with("https://") {
+                "link"
+            } else {
+                word
+            }
+        })
+        .collect();
+
+    if words.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}: {}", msg.sender.name, words.join(" ")))
+}
+
+// Slightly different pitch and speed for every chatter, so that it's easier to follow who speaks
+fn get_voice(settings: &AppSettings, login: &str) -> TtsVoice {
+    if !settings.tts_vary_voices {
+        return TtsVoice {
+            name: settings.tts_voice.clone(),
+            rate: settings.tts_rate,
+            pitch: DEFAULT_PITCH,
+        };
+    }
+
+    let hash = fnv1a_hash(login);
+
+    TtsVoice {
+        name: settings.tts_voice.clone(),
+        // 90% - 110% of configured rate
+        rate: settings.tts_rate * (90 + (hash >> 8) % 21) / 100,
+        pitch: 30 + hash % 41,
+    }
+}
+
+pub struct TtsService {
+    queue: Arc<Mutex<TtsQueue>>,
+}
+
+impl TtsService {
+    pub fn new(engine: Box<dyn TtsEngine>) -> Self {
+        let queue = Arc::new(Mutex::new(TtsQueue::default()));
+
+        let worker_queue = queue.clone();
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let item = {
+                let mut queue = worker_queue.lock().unwrap();
+                queue.skip_current = false;
+                queue.items.pop_front()
+            };
+
+            let item = match item {
+                Some(item) => item,
+                None => continue,
+            };
+
+            let mut playback = match engine.speak(&item.text, &item.voice) {
+                Ok(playback) => playback,
+                Err(err) => {
+                    error!("Failed to speak with {}: {:?}", engine.get_name(), err);
+                    continue;
+                }
+            };
+
+            while !playback.is_finished() {
+                thread::sleep(POLL_INTERVAL);
+
+                if worker_queue.lock().unwrap().skip_current {
+                    playback.stop();
+                    break;
+                }
+            }
+        });
+
+        Self { queue }
+    }
+
+    fn enqueue(&self, item: TtsItem) {
+        let mut queue = self.queue.lock().unwrap();
+
+        if queue.items.len() == MAX_QUEUE_LENGTH {
+            queue.items.pop_front();
+        }
+
+        queue.items.push_back(item);
+    }
+
+    // Queues chat message for speaking if it matches TTS settings
+    pub fn enqueue_message(&self, app_handle: &AppHandle, msg: &PrivmsgMessage) {
+        let settings = app_handle.state::<Mutex<AppSettings>>();
+        let settings = settings.lock().unwrap();
+
+        if !settings.tts_enabled
+            || !matches_policy(msg, settings.tts_policy, &settings.channel_name)
+        {
+            return;
+        }
+
+        let twitch_emotes = EmotesService::parse_twitch_message_emotes(msg);
+        let speech_text = {
+            let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+            let emotes = emotes_state.lock().unwrap();
+
+            get_speech_text(msg, |word| {
+                twitch_emotes.contains_key(word) || emotes.contains_emote(&msg.channel_login, word)
+            })
+        };
+
+        if let Some(text) = speech_text {
+            self.enqueue(TtsItem {
+                text,
+                voice: get_voice(&settings, &msg.sender.login),
+            });
+        }
+    }
+
+    // Stops current message and moves to the next one
+    pub fn skip(&self) {
+        self.queue.lock().unwrap().skip_current = true;
+    }
+
+    // Stops current message and drops all queued ones
+    pub fn clear(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.items.clear();
+        queue.skip_current = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::create_privmsg;
+
+    #[test]
+    fn test_matches_policy_mentions() {
+        let msg = create_privmsg("badges=", "hey @Streamer, look at this");
+
+        assert!(matches_policy(&msg, TtsPolicy::Mentions, "streamer"));
+        assert!(!matches_policy(&msg, TtsPolicy::Mentions, "stream"));
+        assert!(!matches_policy(&msg, TtsPolicy::Mentions, ""));
+    }
+
+    #[test]
+    fn test_matches_policy_highlights() {
+        let highlighted = create_privmsg("badges=;msg-id=highlighted-message", "hello");
+        let regular = create_privmsg("badges=", "hello");
+
+        assert!(matches_policy(&highlighted, TtsPolicy::Highlights, ""));
+        assert!(!matches_policy(&regular, TtsPolicy::Highlights, ""));
+    }
+
+    #[test]
+    fn test_matches_policy_subs_and_bits() {
+        let subscriber = create_privmsg("badges=subscriber/6", "hello");
+        let cheer = create_privmsg("badges=;bits=100", "Cheer100 hello");
+        let regular = create_privmsg("badges=premium/1", "hello");
+
+        assert!(matches_policy(&subscriber, TtsPolicy::SubsAndBits, ""));
+        assert!(matches_policy(&cheer, TtsPolicy::SubsAndBits, ""));
+        assert!(!matches_policy(&regular, TtsPolicy::SubsAndBits, ""));
+    }
+
+    #[test]
+    fn test_get_speech_text_strips_emotes_and_links() {
+        let msg = create_privmsg("badges=", "KEKW look https://example.com KEKW");
+
+        assert_eq!(
+            get_speech_text(&msg, |word| word == "KEKW"),
+            Some("Chatter: look link".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_speech_text_emote_only() {
+        let msg = create_privmsg("badges=", "KEKW KEKW");
+
+        assert_eq!(get_speech_text(&msg, |word| word == "KEKW"), None);
+    }
+
+    #[test]
+    fn test_get_voice_varies_per_user() {
+        let settings = AppSettings::default();
+
+        let first_voice = get_voice(&settings, "first_chatter");
+        let second_voice = get_voice(&settings, "second_chatter");
+
+        assert_eq!(first_voice, get_voice(&settings, "first_chatter"));
+        assert_ne!(first_voice, second_voice);
+        assert!((30..=70).contains(&first_voice.pitch));
+        assert!(first_voice.rate >= settings.tts_rate * 90 / 100);
+        assert!(first_voice.rate <= settings.tts_rate * 110 / 100);
+    }
+
+    #[test]
+    fn test_get_voice_without_variation() {
+        let settings = AppSettings {
+            tts_vary_voices: false,
+            ..AppSettings::default()
+        };
+
+        assert_eq!(
+            get_voice(&settings, "chatter"),
+            TtsVoice {
+                name: "en".to_string(),
+                rate: 175,
+                pitch: DEFAULT_PITCH,
+            }
+        );
+    }
+}
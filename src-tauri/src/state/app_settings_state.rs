@@ -4,15 +4,16 @@ use gigachat_models::AppSettings;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_window_state::{AppHandleExt, StateFlags};
 
-use super::ui_lock_state::UiLockState;
+use crate::{services::OverlayWindowsService, state::ui_lock_state::UiLockState};
 
 pub fn toggle_always_on_top_state(app_handle: &AppHandle, state: bool) {
-    let app_window = app_handle.get_window("main").unwrap();
     let app_state = app_handle.state::<Mutex<AppSettings>>();
 
     app_state.lock().unwrap().always_on_top = state;
 
-    let _ = app_window.set_always_on_top(state);
+    for window in OverlayWindowsService::get_overlay_windows(app_handle) {
+        let _ = window.set_always_on_top(state);
+    }
 
     let _ = app_handle
         .tray_handle()
@@ -21,7 +22,6 @@ pub fn toggle_always_on_top_state(app_handle: &AppHandle, state: bool) {
 }
 
 pub fn toggle_ui_lock_state(app_handle: &AppHandle, is_ui_locked: bool) {
-    let app_window = app_handle.get_window("main").unwrap();
     let ui_lock_state = app_handle.state::<UiLockState>();
     *ui_lock_state.lock().unwrap() = is_ui_locked;
 
@@ -32,7 +32,9 @@ pub fn toggle_ui_lock_state(app_handle: &AppHandle, is_ui_locked: bool) {
         }
     }
 
-    let _ = app_window.set_ignore_cursor_events(is_ui_locked);
+    for window in OverlayWindowsService::get_overlay_windows(app_handle) {
+        let _ = window.set_ignore_cursor_events(is_ui_locked);
+    }
 
     let _ = app_handle
         .tray_handle()
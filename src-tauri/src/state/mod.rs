@@ -1,4 +1,5 @@
 pub mod app_settings_state;
 pub mod current_channel_state;
+pub mod overlay_windows_state;
 pub mod saved_emotes_state;
 pub mod ui_lock_state;
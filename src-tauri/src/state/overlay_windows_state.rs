@@ -0,0 +1,6 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use gigachat_models::AppSettings;
+
+// Settings of additional overlay windows keyed by window label
+pub type OverlayWindowsState = Mutex<HashMap<String, AppSettings>>;
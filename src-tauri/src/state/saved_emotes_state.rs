@@ -1,9 +1,38 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
 
 use tauri::{AppHandle, Manager};
 
-pub type EmotesMap = HashMap<String, String>;
+// Image urls by scale, e.g. 1, 2 and 4
+pub type ScaledUrls = BTreeMap<u32, String>;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmoteUrls {
+    pub urls: ScaledUrls,
+    // Still images of an animated emote
+    pub static_urls: Option<ScaledUrls>,
+    // Set when emotes are fetched, so that providers don't repeat their ids
+    pub provider_id: &'static str,
+    // Id of the emote at its provider, live updates refer to emotes by it
+    pub emote_id: String,
+}
+
+impl EmoteUrls {
+    pub fn new(urls: ScaledUrls, static_urls: Option<ScaledUrls>) -> Self {
+        Self {
+            urls,
+            static_urls,
+            provider_id: "",
+            emote_id: String::new(),
+        }
+    }
+}
+
+pub type EmotesMap = HashMap<String, EmoteUrls>;
+
+// Emotes available in a single channel
 #[derive(Debug, Clone)]
 pub struct SavedEmotes {
     pub global_emotes: EmotesMap,
@@ -17,6 +46,13 @@ impl SavedEmotes {
             channel_emotes,
         }
     }
+
+    // Channel emotes override global emotes with the same code
+    pub fn get_emote(&self, code: &str) -> Option<&EmoteUrls> {
+        self.channel_emotes
+            .get(code)
+            .or_else(|| self.global_emotes.get(code))
+    }
 }
 
 impl Default for SavedEmotes {
@@ -25,17 +61,81 @@ impl Default for SavedEmotes {
     }
 }
 
-pub fn reset_channel_emotes_state(app_handle: &AppHandle) {
-    let saved_emotes_state = app_handle.state::<Mutex<SavedEmotes>>();
-    saved_emotes_state.lock().unwrap().channel_emotes = HashMap::<String, String>::new();
+// Global emotes and emotes of every joined channel, keyed by channel login
+#[derive(Debug, Clone, Default)]
+pub struct EmotesState {
+    pub global_emotes: EmotesMap,
+    pub channels_emotes: HashMap<String, EmotesMap>,
+    // Twitch ids of joined channels keyed by login, used to fetch channel emotes again
+    pub channel_ids: HashMap<String, String>,
+}
+
+impl EmotesState {
+    pub fn get_saved_emotes(&self, channel_login: &str) -> SavedEmotes {
+        SavedEmotes::new(
+            self.global_emotes.clone(),
+            self.channels_emotes
+                .get(channel_login)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+
+    // Same precedence as `SavedEmotes::get_emote`, without cloning the maps
+    pub fn get_emote(&self, channel_login: &str, code: &str) -> Option<&EmoteUrls> {
+        self.channels_emotes
+            .get(channel_login)
+            .and_then(|channel_emotes| channel_emotes.get(code))
+            .or_else(|| self.global_emotes.get(code))
+    }
+
+    pub fn contains_emote(&self, channel_login: &str, word: &str) -> bool {
+        self.global_emotes.contains_key(word)
+            || self
+                .channels_emotes
+                .get(channel_login)
+                .map_or(false, |channel_emotes| channel_emotes.contains_key(word))
+    }
+}
+
+pub fn get_saved_emotes_state(app_handle: &AppHandle, channel_login: &str) -> SavedEmotes {
+    let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+    let emotes_state = emotes_state.lock().unwrap();
+
+    emotes_state.get_saved_emotes(channel_login)
+}
+
+pub fn reset_channel_emotes_state(app_handle: &AppHandle, channel_login: &str) {
+    let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+    let mut emotes_state = emotes_state.lock().unwrap();
+
+    emotes_state.channels_emotes.remove(channel_login);
+    emotes_state.channel_ids.remove(channel_login);
+}
+
+pub fn update_channel_id_state(app_handle: &AppHandle, channel_login: &str, channel_id: &str) {
+    let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+    emotes_state
+        .lock()
+        .unwrap()
+        .channel_ids
+        .insert(channel_login.to_string(), channel_id.to_string());
 }
 
-pub fn update_channel_emotes_state(app_handle: &AppHandle, channel_emotes: EmotesMap) {
-    let saved_emotes_state = app_handle.state::<Mutex<SavedEmotes>>();
-    saved_emotes_state.lock().unwrap().channel_emotes = channel_emotes;
+pub fn update_channel_emotes_state(
+    app_handle: &AppHandle,
+    channel_login: &str,
+    channel_emotes: EmotesMap,
+) {
+    let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+    emotes_state
+        .lock()
+        .unwrap()
+        .channels_emotes
+        .insert(channel_login.to_string(), channel_emotes);
 }
 
 pub fn update_global_emotes_state(app_handle: &AppHandle, global_emotes: EmotesMap) {
-    let saved_emotes_state = app_handle.state::<Mutex<SavedEmotes>>();
-    saved_emotes_state.lock().unwrap().global_emotes = global_emotes;
+    let emotes_state = app_handle.state::<Mutex<EmotesState>>();
+    emotes_state.lock().unwrap().global_emotes = global_emotes;
 }
@@ -0,0 +1,58 @@
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+
+use super::{TtsEngine, TtsPlayback, TtsVoice};
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+// Offline engine using `espeak-ng` executable, which has to be available in PATH
+#[derive(Debug, Default)]
+pub struct EspeakTtsEngine {}
+
+struct EspeakPlayback {
+    process: Child,
+}
+
+impl TtsPlayback for EspeakPlayback {
+    fn is_finished(&mut self) -> bool {
+        !matches!(self.process.try_wait(), Ok(None))
+    }
+
+    fn stop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+impl TtsEngine for EspeakTtsEngine {
+    fn get_name(&self) -> &'static str {
+        "espeak-ng"
+    }
+
+    fn speak(&self, text: &str, voice: &TtsVoice) -> anyhow::Result<Box<dyn TtsPlayback>> {
+        let mut command = Command::new("espeak-ng");
+        command
+            .arg("-v")
+            .arg(&voice.name)
+            .arg("-s")
+            .arg(voice.rate.to_string())
+            .arg("-p")
+            .arg(voice.pitch.to_string())
+            // End of options, so that messages starting with `-` are not treated as flags
+            .arg("--")
+            .arg(text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        // Don't flash console window for every message
+        #[cfg(windows)]
+        command.creation_flags(CREATE_NO_WINDOW);
+
+        let process = command.spawn()?;
+
+        Ok(Box::new(EspeakPlayback { process }))
+    }
+}
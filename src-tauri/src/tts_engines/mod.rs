@@ -0,0 +1,24 @@
+pub mod espeak_tts_engine;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TtsVoice {
+    pub name: String,
+    // Words per minute
+    pub rate: u32,
+    // 0-100, 50 is the engine default
+    pub pitch: u32,
+}
+
+// Speech which is being played by an engine
+pub trait TtsPlayback: Send {
+    fn is_finished(&mut self) -> bool;
+
+    fn stop(&mut self);
+}
+
+pub trait TtsEngine: Send + Sync {
+    fn get_name(&self) -> &'static str;
+
+    // Starts speaking without waiting for the speech to end
+    fn speak(&self, text: &str, voice: &TtsVoice) -> anyhow::Result<Box<dyn TtsPlayback>>;
+}
@@ -1,10 +1,55 @@
 use gigachat_models::{
     AppSettings, GigaChatMessage, UpdateAlwaysOnTopEventArgs, UpdateIgnoreEventsArgs,
+    UpdateInteractionEventArgs, UpdateMessageEventArgs,
 };
-use tauri::{AppHandle, LogicalSize, Manager};
+use tauri::{AppHandle, LogicalSize, Manager, Window};
 
-pub fn emit_new_message<R: tauri::Runtime>(message: GigaChatMessage, manager: &impl Manager<R>) {
-    manager.emit_all("new_message", message).unwrap();
+use crate::services::{OverlayEvent, OverlayServerService, MAIN_WINDOW_LABEL};
+
+#[cfg(test)]
+pub mod test_utils;
+
+// Invisible characters chat clients append to bypass Twitch duplicate message check.
+// Tag characters after `U+E0000` are kept, they are a part of subdivision flag emoji
+pub const INVISIBLE_CHARACTERS: [char; 5] =
+    ['\u{E0000}', '\u{034F}', '\u{200B}', '\u{2060}', '\u{FEFF}'];
+
+const SETTINGS_WINDOW_LABEL: &str = "settings";
+const EMOTES_WINDOW_LABEL: &str = "emotes";
+
+// Browser sources connected to the overlay server receive the same events as app windows
+fn publish_overlay_event<R: tauri::Runtime>(manager: &impl Manager<R>, event: OverlayEvent) {
+    manager.state::<OverlayServerService>().publish(event);
+}
+
+// Messages are filtered by settings of every overlay window, so they are sent to one window only.
+// Browser sources show the main overlay
+pub fn emit_new_messages<R: tauri::Runtime>(
+    messages: Vec<GigaChatMessage>,
+    window_label: &str,
+    manager: &impl Manager<R>,
+) {
+    if window_label == MAIN_WINDOW_LABEL {
+        publish_overlay_event(manager, OverlayEvent::NewMessages(messages.clone()));
+    }
+
+    manager
+        .emit_to(window_label, "new_messages", messages)
+        .unwrap();
+}
+
+pub fn emit_message_updated<R: tauri::Runtime>(
+    value: UpdateMessageEventArgs,
+    window_label: &str,
+    manager: &impl Manager<R>,
+) {
+    if window_label == MAIN_WINDOW_LABEL {
+        publish_overlay_event(manager, OverlayEvent::MessageUpdated(value.clone()));
+    }
+
+    manager
+        .emit_to(window_label, "message_updated", value)
+        .unwrap();
 }
 
 pub fn emit_toggle_ui_lock<R: tauri::Runtime>(
@@ -14,6 +59,13 @@ pub fn emit_toggle_ui_lock<R: tauri::Runtime>(
     manager.emit_all("toggle_ui_lock", value).unwrap();
 }
 
+pub fn emit_toggle_interaction<R: tauri::Runtime>(
+    manager: &impl Manager<R>,
+    value: UpdateInteractionEventArgs,
+) {
+    manager.emit_all("toggle_interaction", value).unwrap();
+}
+
 pub fn emit_toggle_always_on_top<R: tauri::Runtime>(
     manager: &impl Manager<R>,
     value: UpdateAlwaysOnTopEventArgs,
@@ -21,16 +73,51 @@ pub fn emit_toggle_always_on_top<R: tauri::Runtime>(
     manager.emit_all("toggle_always_on_top", value).unwrap();
 }
 
-pub fn emit_init_settings<R: tauri::Runtime>(manager: &impl Manager<R>, settings: &AppSettings) {
-    info!("Emitting init_settings");
+pub fn emit_init_settings<R: tauri::Runtime>(window: &Window<R>, settings: &AppSettings) {
+    info!("Emitting init_settings to '{}'", window.label());
+
+    window.emit("init_settings", settings).unwrap();
+}
+
+// App settings are shown by the main overlay and edited in the settings window,
+// additional overlay windows have their own settings
+pub fn emit_sync_settings<R: tauri::Runtime>(manager: &impl Manager<R>, settings: &AppSettings) {
+    publish_overlay_event(manager, OverlayEvent::SyncSettings(settings.clone()));
+
+    manager
+        .emit_to(MAIN_WINDOW_LABEL, "sync_settings", settings)
+        .unwrap();
+    manager
+        .emit_to(SETTINGS_WINDOW_LABEL, "sync_settings", settings)
+        .unwrap();
+}
 
-    manager.emit_all("init_settings", settings).unwrap();
+pub fn emit_sync_window_settings<R: tauri::Runtime>(
+    manager: &impl Manager<R>,
+    window_label: &str,
+    settings: &AppSettings,
+) {
+    manager
+        .emit_to(window_label, "sync_settings", settings)
+        .unwrap();
+}
+
+pub fn emit_clear_chat<R: tauri::Runtime>(manager: &impl Manager<R>) {
+    publish_overlay_event(manager, OverlayEvent::ClearChat);
+
+    manager.emit_all("clear_chat", ()).unwrap();
+}
+
+pub fn emit_theme_updated<R: tauri::Runtime>(manager: &impl Manager<R>) {
+    publish_overlay_event(manager, OverlayEvent::ThemeUpdated);
+
+    manager.emit_all("theme_updated", ()).unwrap();
 }
 
 pub fn create_settings_window(app_handle: &AppHandle) -> tauri::Window {
     let settings_window = tauri::WindowBuilder::new(
         app_handle,
-        "settings",
+        SETTINGS_WINDOW_LABEL,
         tauri::WindowUrl::App("/settings".into()),
     )
     .resizable(false)
@@ -48,7 +135,7 @@ pub fn create_settings_window(app_handle: &AppHandle) -> tauri::Window {
 
 pub fn open_settings_window(app_handle: &AppHandle) -> tauri::Window {
     let window = app_handle
-        .get_window("settings")
+        .get_window(SETTINGS_WINDOW_LABEL)
         .unwrap_or_else(|| create_settings_window(app_handle));
 
     let _ = window.set_size(LogicalSize {
@@ -59,8 +146,28 @@ pub fn open_settings_window(app_handle: &AppHandle) -> tauri::Window {
     window
 }
 
+pub fn open_emotes_window(app_handle: &AppHandle) -> tauri::Window {
+    if let Some(window) = app_handle.get_window(EMOTES_WINDOW_LABEL) {
+        let _ = window.set_focus();
+        return window;
+    }
+
+    tauri::WindowBuilder::new(
+        app_handle,
+        EMOTES_WINDOW_LABEL,
+        tauri::WindowUrl::App("/emotes".into()),
+    )
+    .resizable(true)
+    .decorations(true)
+    .center()
+    .inner_size(480_f64, 600_f64)
+    .title("Emotes")
+    .build()
+    .expect("Failed to create emotes window")
+}
+
 pub fn close_settings_window(app_handle: &AppHandle) {
-    let settings_window = app_handle.get_window("settings");
+    let settings_window = app_handle.get_window(SETTINGS_WINDOW_LABEL);
 
     if let Some(settings_window) = settings_window {
         settings_window
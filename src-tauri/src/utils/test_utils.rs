@@ -0,0 +1,50 @@
+use twitch_irc::message::{IRCMessage, PrivmsgMessage, ServerMessage};
+
+const DEFAULT_TAGS: [(&str, &str); 14] = [
+    ("badge-info", ""),
+    ("badges", ""),
+    ("color", "#FF0000"),
+    ("display-name", "Chatter"),
+    ("emotes", ""),
+    ("flags", ""),
+    ("id", "b34ccfc7-4977-403a-8a94-33c6bac34fb8"),
+    ("mod", "0"),
+    ("room-id", "22484632"),
+    ("subscriber", "0"),
+    ("tmi-sent-ts", "1676000000000"),
+    ("turbo", "0"),
+    ("user-id", "100000"),
+    ("user-type", ""),
+];
+
+// Chat message as received from Twitch IRC, `tags` replace or extend the default ones,
+// e.g. `badges=subscriber/6;bits=100` or `emotes=25:0-4`
+pub fn create_privmsg(tags: &str, text: &str) -> PrivmsgMessage {
+    let overrides: Vec<(&str, &str)> = tags
+        .split(';')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.split_once('=').unwrap_or((tag, "")))
+        .collect();
+
+    let tags: Vec<String> = DEFAULT_TAGS
+        .iter()
+        .filter(|(key, _)| {
+            !overrides
+                .iter()
+                .any(|(override_key, _)| override_key == key)
+        })
+        .chain(overrides.iter())
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+
+    let raw_message = format!(
+        "@{} :chatter!chatter@chatter.tmi.twitch.tv PRIVMSG #channel :{}",
+        tags.join(";"),
+        text
+    );
+
+    match ServerMessage::try_from(IRCMessage::parse(&raw_message).unwrap()).unwrap() {
+        ServerMessage::Privmsg(msg) => msg,
+        _ => panic!("Expected privmsg"),
+    }
+}